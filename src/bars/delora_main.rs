@@ -14,11 +14,12 @@ use crate::{
     audio, cmd,
     datetime::{clock_comp, date_comp},
     feature::{Comp, CompWithProps, Feature, Service},
+    idle::caffeine_comp,
     niri::{state_serv, win_comp, ws_comp},
     powermenu::button_comp,
     system_info as sys_info,
     theme::CAT_THEME,
-    tray::{TrayBar, TrayLayout, service as tray_serv, tray_comp},
+    tray::{TrayBar, TrayMenu, service as tray_serv, tray_comp},
     types::MonitorId,
     widget::{
         align_center, bar_widgets,
@@ -43,12 +44,14 @@ pub enum Message {
         /// sn item name
         String,
         /// menu layout
-        TrayLayout,
+        TrayMenu,
     ),
     PowerButtonOnClicked,
+    CaffeineOnClicked,
 
     SysInfo(sys_info::Message),
     PowerBtn(button_comp::Message),
+    Caffeine(caffeine_comp::Message),
 
     Conn(cmd::Message),
     Audio(audio::Message),
@@ -72,6 +75,7 @@ pub struct DeloraMain {
     tray: tray_comp::TrayComp,
     sys_info: sys_info::SysInfoComp,
     power_btn: button_comp::PowerButton,
+    caffeine: caffeine_comp::CaffeineButton,
     conn: cmd::CmdComp,
     audio: audio::PulseAudio,
 }
@@ -112,9 +116,9 @@ impl Comp for DeloraMain {
         let (niri_serv, niri_serv_task) = state_serv::NiriStateServ::new((), Message::NiriService);
         let (tray_serv, tray_serv_task) = tray_serv::TrayService::new((), Message::TrayService);
         let (tray, tray_task) = tray_comp::TrayComp::new((), Message::Tray);
-        let (sys_info, sys_info_task) =
-            sys_info::SysInfoComp::new(sys_info::Init { bat_name: None }, Message::SysInfo);
+        let (sys_info, sys_info_task) = sys_info::SysInfoComp::new((), Message::SysInfo);
         let (power_btn, power_btn_task) = button_comp::PowerButton::new((), Message::PowerBtn);
+        let (caffeine, caffeine_task) = caffeine_comp::CaffeineButton::new((), Message::Caffeine);
         let (conn, conn_task) = cmd::CmdComp::new(
             cmd::Init {
                 cmd: "connectivity".to_owned(),
@@ -135,6 +139,7 @@ impl Comp for DeloraMain {
             tray_task,
             sys_info_task,
             power_btn_task,
+            caffeine_task,
             conn_task,
             audio_task,
         ]);
@@ -153,6 +158,7 @@ impl Comp for DeloraMain {
                 tray,
                 sys_info,
                 power_btn,
+                caffeine,
                 conn,
                 audio,
             },
@@ -175,8 +181,8 @@ impl Comp for DeloraMain {
             Message::Tray(message) => {
                 let inner_task = self.tray.update(message.clone()).map(Message::Tray);
                 let out_task = match message {
-                    tray_comp::Message::SnItemClicked(name, layout) => {
-                        Task::done(Message::OpenTrayMenu(name, layout))
+                    tray_comp::Message::SnItemClicked(name, menu) => {
+                        Task::done(Message::OpenTrayMenu(name, menu))
                     } // _ => Task::none(),
                 };
                 inner_task.chain(out_task)
@@ -184,14 +190,26 @@ impl Comp for DeloraMain {
             Message::OpenTrayMenu(_, _) => Task::none(),
             Message::SysInfo(message) => self.sys_info.update(message).map(Message::SysInfo),
             Message::PowerBtn(message) => {
-                let inner_task = self.power_btn.update(message).map(Message::PowerBtn);
+                let inner_task = self
+                    .power_btn
+                    .update(message.clone())
+                    .map(Message::PowerBtn);
                 let out_task = match message {
                     button_comp::Message::OnClick => Task::done(Message::PowerButtonOnClicked),
-                    // _ => Task::none(),
+                    _ => Task::none(),
                 };
                 inner_task.chain(out_task)
             }
             Message::PowerButtonOnClicked => Task::none(),
+            Message::Caffeine(message) => {
+                let inner_task = self.caffeine.update(message.clone()).map(Message::Caffeine);
+                let out_task = match message {
+                    caffeine_comp::Message::OnClick => Task::done(Message::CaffeineOnClicked),
+                    _ => Task::none(),
+                };
+                inner_task.chain(out_task)
+            }
+            Message::CaffeineOnClicked => Task::none(),
             Message::Conn(message) => self.conn.update(message).map(Message::Conn),
             Message::Audio(message) => self.audio.update(message).map(Message::Audio),
         }
@@ -206,11 +224,13 @@ impl Comp for DeloraMain {
         let tray_serv = self.tray_serv.subscription().map(Message::TrayService);
         let tray = self.tray.subscription().map(Message::Tray);
         let sys_info = self.sys_info.subscription().map(Message::SysInfo);
+        let power_btn = self.power_btn.subscription().map(Message::PowerBtn);
         let conn = self.conn.subscription().map(Message::Conn);
         let audio = self.audio.subscription().map(Message::Audio);
 
         Subscription::batch([
-            clock, date, niri_ws, niri_win, niri_serv, tray_serv, tray, sys_info, conn, audio,
+            clock, date, niri_ws, niri_win, niri_serv, tray_serv, tray, sys_info, power_btn, conn,
+            audio,
         ])
     }
 
@@ -265,6 +285,7 @@ impl Comp for DeloraMain {
             .map(Message::Tray);
 
         let power_btn = self.power_btn.view().map(Message::PowerBtn);
+        let caffeine = self.caffeine.view().map(Message::Caffeine);
 
         let audio = {
             let vol = self.audio.get_vol();
@@ -360,7 +381,7 @@ impl Comp for DeloraMain {
         bar_widgets!(
             left:  date_view, div, niri_ws_view;
             center: clock_view, win_div, win, tray;
-            right: power_btn, audio, conn, sys_view, disk_usage
+            right: power_btn, caffeine, audio, conn, sys_view, disk_usage
         )
         .background(Color::TRANSPARENT)
         .padding(padding::horizontal(spacing.md()).top(self.padding))
@@ -8,11 +8,12 @@ use iced_layershell::reexport::{
 use crate::{
     datetime::{clock_comp, date_comp},
     feature::{Comp, CompWithProps, Feature, Service},
+    idle::caffeine_comp,
     niri::{state_serv, win_comp, ws_comp},
     powermenu::button_comp,
     system_info as sys_info,
     theme::CAT_THEME,
-    tray::{TrayBar, TrayLayout, service as tray_serv, tray_comp},
+    tray::{TrayBar, TrayMenu, service as tray_serv, tray_comp},
     types::MonitorId,
     widget::{
         align_center, bar_widgets,
@@ -36,12 +37,14 @@ pub enum Message {
         /// sni item name
         String,
         /// menu layout
-        TrayLayout,
+        TrayMenu,
     ),
     PowerButtonOnClicked,
+    CaffeineOnClicked,
 
     SysInfo(sys_info::Message),
     PowerBtn(button_comp::Message),
+    Caffeine(caffeine_comp::Message),
 }
 
 pub struct Init {
@@ -62,6 +65,7 @@ pub struct RenaMain {
     tray: tray_comp::TrayComp,
     sys_info: sys_info::SysInfoComp,
     power_btn: button_comp::PowerButton,
+    caffeine: caffeine_comp::CaffeineButton,
 }
 
 impl RenaMain {
@@ -100,9 +104,9 @@ impl Comp for RenaMain {
         let (niri_serv, niri_serv_task) = state_serv::NiriStateServ::new((), Message::NiriService);
         let (tray_serv, tray_serv_task) = tray_serv::TrayService::new((), Message::TrayService);
         let (tray, tray_task) = tray_comp::TrayComp::new((), Message::Tray);
-        let (sys_info, sys_info_task) =
-            sys_info::SysInfoComp::new(sys_info::Init { bat_name: None }, Message::SysInfo);
+        let (sys_info, sys_info_task) = sys_info::SysInfoComp::new((), Message::SysInfo);
         let (power_btn, power_btn_task) = button_comp::PowerButton::new((), Message::PowerBtn);
+        let (caffeine, caffeine_task) = caffeine_comp::CaffeineButton::new((), Message::Caffeine);
 
         let inner_tasks = Task::batch([
             win_comp_task,
@@ -114,6 +118,7 @@ impl Comp for RenaMain {
             tray_task,
             sys_info_task,
             power_btn_task,
+            caffeine_task,
         ]);
 
         (
@@ -130,6 +135,7 @@ impl Comp for RenaMain {
                 tray,
                 sys_info,
                 power_btn,
+                caffeine,
             },
             inner_tasks.map(f),
         )
@@ -144,9 +150,10 @@ impl Comp for RenaMain {
         let tray_serv = self.tray_serv.subscription().map(Message::TrayService);
         let tray = self.tray.subscription().map(Message::Tray);
         let sys_info = self.sys_info.subscription().map(Message::SysInfo);
+        let power_btn = self.power_btn.subscription().map(Message::PowerBtn);
 
         Subscription::batch([
-            clock, date, niri_ws, niri_win, niri_serv, tray_serv, tray, sys_info,
+            clock, date, niri_ws, niri_win, niri_serv, tray_serv, tray, sys_info, power_btn,
         ])
     }
 
@@ -165,8 +172,8 @@ impl Comp for RenaMain {
             Message::Tray(message) => {
                 let inner_task = self.tray.update(message.clone()).map(Message::Tray);
                 let out_task = match message {
-                    tray_comp::Message::SnItemClicked(name, layout) => {
-                        Task::done(Message::OpenTrayMenu(name, layout))
+                    tray_comp::Message::SnItemClicked(name, menu) => {
+                        Task::done(Message::OpenTrayMenu(name, menu))
                     }
                 };
                 inner_task.chain(out_task)
@@ -174,13 +181,26 @@ impl Comp for RenaMain {
             Message::OpenTrayMenu(_, _) => Task::none(),
             Message::SysInfo(message) => self.sys_info.update(message).map(Message::SysInfo),
             Message::PowerBtn(message) => {
-                let inner_task = self.power_btn.update(message).map(Message::PowerBtn);
+                let inner_task = self
+                    .power_btn
+                    .update(message.clone())
+                    .map(Message::PowerBtn);
                 let out_task = match message {
                     button_comp::Message::OnClick => Task::done(Message::PowerButtonOnClicked),
+                    _ => Task::none(),
                 };
                 inner_task.chain(out_task)
             }
             Message::PowerButtonOnClicked => Task::none(),
+            Message::Caffeine(message) => {
+                let inner_task = self.caffeine.update(message.clone()).map(Message::Caffeine);
+                let out_task = match message {
+                    caffeine_comp::Message::OnClick => Task::done(Message::CaffeineOnClicked),
+                    _ => Task::none(),
+                };
+                inner_task.chain(out_task)
+            }
+            Message::CaffeineOnClicked => Task::none(),
         }
     }
 
@@ -235,6 +255,7 @@ impl Comp for RenaMain {
             .map(Message::Tray);
 
         let power_btn = self.power_btn.view().map(Message::PowerBtn);
+        let caffeine = self.caffeine.view().map(Message::Caffeine);
 
         let sys_view = {
             let div = Semi::new(
@@ -254,7 +275,7 @@ impl Comp for RenaMain {
         bar_widgets!(
             left:  date_view, div, niri_ws_view;
             center: clock_view, win_div, win, tray;
-            right: power_btn, sys_view, end_cap
+            right: power_btn, caffeine, sys_view, end_cap
         )
         .background(Color::TRANSPARENT)
         .padding(padding::horizontal(spacing.md()).top(self.padding))
@@ -1,8 +1,8 @@
 use iced::{
     Length, Subscription, Task,
     alignment::Vertical,
-    padding,
-    widget::{container, row, text},
+    border, padding,
+    widget::{container, row, text, tooltip::Position},
 };
 use iced_layershell::reexport::{
     Anchor, KeyboardInteractivity, Layer, NewLayerShellSettings, OutputOption,
@@ -88,12 +88,7 @@ impl Comp for RenaSec {
             Message::Btc,
         );
 
-        let (sys_info, sys_info_task) = system_info::SysInfoComp::new(
-            system_info::Init {
-                bat_name: Some("BAT1".to_owned()),
-            },
-            Message::SysInfo,
-        );
+        let (sys_info, sys_info_task) = system_info::SysInfoComp::new((), Message::SysInfo);
         let (conn, conn_task) = cmd::CmdComp::new(
             cmd::Init {
                 cmd: "connectivity".to_owned(),
@@ -269,21 +264,24 @@ impl Comp for RenaSec {
                 }
                 BatteryState::Charging(_) => theme.green(),
                 BatteryState::Low(_) => theme.red(),
-                BatteryState::Full | BatteryState::None => theme.text_color(),
+                BatteryState::Full | BatteryState::NotCharging(_) | BatteryState::None => {
+                    theme.text_color()
+                }
             };
             let text = match bat_state {
                 BatteryState::None => "N/A".to_owned(),
                 BatteryState::Full => "100".to_owned(),
                 BatteryState::Charging(cap)
                 | BatteryState::Discharging(cap)
-                | BatteryState::Low(cap) => format!("{}", cap),
+                | BatteryState::Low(cap)
+                | BatteryState::NotCharging(cap) => format!("{}", cap),
             };
             let text = text!("{text}").color(color);
             let icon = match bat_state {
                 BatteryState::None => lucide::icon_battery_warning(),
                 BatteryState::Charging(_) => lucide::icon_battery_charging(),
                 BatteryState::Full => lucide::icon_battery_full(),
-                BatteryState::Discharging(cap) => {
+                BatteryState::Discharging(cap) | BatteryState::NotCharging(cap) => {
                     if cap > &90.0 {
                         lucide::icon_battery_full()
                     } else {
@@ -295,6 +293,16 @@ impl Comp for RenaSec {
             .size(spacing.md())
             .center()
             .color(color);
+            // the charging icon already shows it
+            let ac_icon: Option<iced::Element<_>> = (self.sys_info.is_ac_online()
+                && !matches!(bat_state, BatteryState::Charging(_)))
+            .then(|| {
+                lucide::icon_plug()
+                    .size(spacing.md())
+                    .center()
+                    .color(theme.green())
+                    .into()
+            });
 
             let div = Angled::new(
                 theme.surface0(),
@@ -306,12 +314,32 @@ impl Comp for RenaSec {
 
             let content = align_center!(
                 row![icon, text]
+                    .push(ac_icon)
                     .spacing(spacing.xs())
                     .align_y(Vertical::Center)
             )
             .padding(padding::horizontal(spacing.sm()))
             .background(theme.surface0());
 
+            let time_left = self.sys_info.bat_time_left().map(|left| {
+                let mins = left.as_secs() / 60;
+                let until = if matches!(bat_state, BatteryState::Charging(_)) {
+                    "full"
+                } else {
+                    "empty"
+                };
+                container(text!("{}h {:02}m until {until}", mins / 60, mins % 60))
+                    .padding(padding::horizontal(spacing.sm()).vertical(spacing.xxs()))
+                    .style(move |_| container::Style {
+                        background: Some(theme.background().into()),
+                        border: border::rounded(theme.radius().md())
+                            .width(spacing.xxs())
+                            .color(color),
+                        ..Default::default()
+                    })
+            });
+            let content = content.maybe_tooltip(Position::Left, time_left);
+
             row![div, content].align_y(Vertical::Center)
         };
 
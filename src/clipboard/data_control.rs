@@ -0,0 +1,358 @@
+//! clipboard access through wlr-data-control, lets us watch and set the
+//! selection without a focused surface
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, PipeReader, Read, Write},
+    os::fd::{AsFd, AsRawFd},
+    thread,
+    time::{Duration, Instant},
+};
+
+use iced::futures::{Stream, channel::mpsc};
+use tracing::info;
+use wayland_client::{
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+    backend::ObjectId,
+    event_created_child,
+    globals::{GlobalListContents, registry_queue_init},
+    protocol::{wl_registry::WlRegistry, wl_seat::WlSeat},
+};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_offer_v1::{self, ZwlrDataControlOfferV1},
+    zwlr_data_control_source_v1::{self, ZwlrDataControlSourceV1},
+};
+
+/// text mime types in order of preference
+pub const TEXT_MIMES: [&str; 5] = [
+    "text/plain;charset=utf-8",
+    "UTF8_STRING",
+    "text/plain",
+    "TEXT",
+    "STRING",
+];
+/// image mime types in order of preference
+pub const IMAGE_MIMES: [&str; 4] = ["image/png", "image/jpeg", "image/webp", "image/gif"];
+/// offered by password managers for selections that shouldn't be kept
+const SECRET_MIME: &str = "x-kde-passwordManagerHint";
+/// how long the copying client gets to send the selection
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// the contents of a selection
+#[derive(Debug, Clone)]
+pub struct Clip {
+    pub mime: String,
+    pub data: Vec<u8>,
+}
+
+impl Clip {
+    pub fn text(text: &str) -> Self {
+        Self {
+            mime: TEXT_MIMES[0].to_owned(),
+            data: text.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        TEXT_MIMES.contains(&self.mime.as_str())
+    }
+}
+
+struct State {
+    /// mime types of the offers that haven't been announced as a selection yet
+    offers: HashMap<ObjectId, Vec<String>>,
+    /// the current selection, None once it's been read
+    selection: Option<(ZwlrDataControlOfferV1, Vec<String>)>,
+    /// the clip served while we own the selection
+    source: Option<Clip>,
+    finished: bool,
+}
+
+struct Client {
+    conn: Connection,
+    queue: EventQueue<State>,
+    state: State,
+    manager: ZwlrDataControlManagerV1,
+    device: ZwlrDataControlDeviceV1,
+}
+
+impl Client {
+    fn connect() -> anyhow::Result<Self> {
+        let conn = Connection::connect_to_env()?;
+        let (globals, queue) = registry_queue_init::<State>(&conn)?;
+        let qh = queue.handle();
+        let seat: WlSeat = globals.bind(&qh, 1..=1, ())?;
+        let manager: ZwlrDataControlManagerV1 = globals.bind(&qh, 1..=2, ())?;
+        let device = manager.get_data_device(&seat, &qh, ());
+
+        Ok(Self {
+            conn,
+            queue,
+            state: State {
+                offers: HashMap::new(),
+                selection: None,
+                source: None,
+                finished: false,
+            },
+            manager,
+            device,
+        })
+    }
+
+    fn dispatch(&mut self) -> anyhow::Result<()> {
+        self.queue.blocking_dispatch(&mut self.state)?;
+        if self.state.finished {
+            anyhow::bail!("Data control device finished");
+        }
+        Ok(())
+    }
+
+    /// read the current selection, text is preferred over images
+    fn read_selection(&mut self, max_bytes: usize) -> anyhow::Result<Option<Clip>> {
+        let Some((offer, mimes)) = self.state.selection.take() else {
+            return Ok(None);
+        };
+
+        let mime = TEXT_MIMES
+            .iter()
+            .chain(IMAGE_MIMES.iter())
+            .find(|mime| mimes.iter().any(|offered| offered == *mime));
+        let Some(mime) = mime.filter(|_| !mimes.iter().any(|mime| mime == SECRET_MIME)) else {
+            offer.destroy();
+            return Ok(None);
+        };
+
+        let (reader, writer) = io::pipe()?;
+        offer.receive(mime.to_string(), writer.as_fd());
+        self.conn.flush()?;
+        drop(writer);
+
+        let data = read_timeout(reader, max_bytes + 1);
+        offer.destroy();
+        let data = match data {
+            Ok(data) => data,
+            Err(err) => {
+                info!("Error reading selection: {err:?}");
+                return Ok(None);
+            }
+        };
+
+        if data.is_empty() || data.len() > max_bytes {
+            return Ok(None);
+        }
+
+        Ok(Some(Clip {
+            mime: mime.to_string(),
+            data,
+        }))
+    }
+}
+
+/// read up to `limit` bytes, giving up once `READ_TIMEOUT` passes so a
+/// misbehaving client can't hang the watch
+fn read_timeout(mut reader: PipeReader, limit: usize) -> anyhow::Result<Vec<u8>> {
+    let deadline = Instant::now() + READ_TIMEOUT;
+    let mut data = Vec::new();
+    let mut buf = [0; 8192];
+
+    while data.len() < limit {
+        let timeout_ms = deadline
+            .saturating_duration_since(Instant::now())
+            .as_millis() as i32;
+        let mut fds = [libc::pollfd {
+            fd: reader.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), 1, timeout_ms) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err.into());
+        }
+        if ready == 0 {
+            anyhow::bail!("Timed out after {READ_TIMEOUT:?}");
+        }
+
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..read]);
+    }
+    data.truncate(limit);
+
+    Ok(data)
+}
+
+/// stream the selections as they're copied, larger than `max_bytes` are skipped
+/// reads block, so the wayland connection lives on its own thread
+pub fn watch(max_bytes: usize) -> impl Stream<Item = Clip> {
+    let (tx, rx) = mpsc::unbounded();
+
+    thread::spawn(move || {
+        let res = (|| -> anyhow::Result<()> {
+            let mut client = Client::connect()?;
+            loop {
+                client.dispatch()?;
+                if let Some(clip) = client.read_selection(max_bytes)?
+                    && tx.unbounded_send(clip).is_err()
+                {
+                    return Ok(());
+                }
+            }
+        })();
+
+        if let Err(err) = res {
+            info!("Error watching clipboard: {err:?}");
+        }
+    });
+
+    rx
+}
+
+/// take the selection and serve the clip until something else is copied
+pub fn copy(clip: Clip) {
+    thread::spawn(move || {
+        let res = (|| -> anyhow::Result<()> {
+            let mut client = Client::connect()?;
+            let qh = client.queue.handle();
+            let source = client.manager.create_data_source(&qh, ());
+            if clip.is_text() {
+                TEXT_MIMES
+                    .iter()
+                    .for_each(|mime| source.offer(mime.to_string()));
+            } else {
+                source.offer(clip.mime.clone());
+            }
+            client.device.set_selection(Some(&source));
+            client.state.source = Some(clip);
+
+            while client.state.source.is_some() {
+                client.dispatch()?;
+            }
+            Ok(())
+        })();
+
+        if let Err(err) = res {
+            info!("Error setting clipboard: {err:?}");
+        }
+    });
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlRegistry,
+        _event: <WlRegistry as Proxy>::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSeat,
+        _event: <WlSeat as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlManagerV1, ()> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwlrDataControlManagerV1,
+        _event: <ZwlrDataControlManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlDeviceV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwlrDataControlDeviceV1,
+        event: zwlr_data_control_device_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_device_v1::Event::DataOffer { id } => {
+                state.offers.insert(id.id(), Vec::new());
+            }
+            zwlr_data_control_device_v1::Event::Selection { id } => {
+                if let Some((old, _)) = state.selection.take() {
+                    old.destroy();
+                }
+                state.selection = id.map(|offer| {
+                    let mimes = state.offers.remove(&offer.id()).unwrap_or_default();
+                    (offer, mimes)
+                });
+            }
+            zwlr_data_control_device_v1::Event::PrimarySelection { id: Some(offer) } => {
+                state.offers.remove(&offer.id());
+                offer.destroy();
+            }
+            zwlr_data_control_device_v1::Event::Finished => state.finished = true,
+            _ => (),
+        }
+    }
+
+    event_created_child!(State, ZwlrDataControlDeviceV1, [
+        zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrDataControlOfferV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        offer: &ZwlrDataControlOfferV1,
+        event: zwlr_data_control_offer_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let zwlr_data_control_offer_v1::Event::Offer { mime_type } = event {
+            state.offers.entry(offer.id()).or_default().push(mime_type);
+        }
+    }
+}
+
+impl Dispatch<ZwlrDataControlSourceV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        source: &ZwlrDataControlSourceV1,
+        event: zwlr_data_control_source_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_source_v1::Event::Send { fd, .. } => {
+                if let Some(clip) = &state.source
+                    && let Err(err) = File::from(fd).write_all(&clip.data)
+                {
+                    info!("Error sending clipboard: {err:?}");
+                }
+            }
+            zwlr_data_control_source_v1::Event::Cancelled => {
+                source.destroy();
+                state.source = None;
+            }
+            _ => (),
+        }
+    }
+}
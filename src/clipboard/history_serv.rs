@@ -0,0 +1,264 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use derive_more::{Deref, DerefMut};
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::info;
+
+use crate::{
+    clipboard::data_control::{self, Clip},
+    config::CONFIG,
+    feature::Service,
+};
+
+const MAX_ENTRIES: usize = 200;
+const MAX_BYTES: usize = 5 * 1024 * 1024;
+/// max width and height of the stored images
+const THUMB_SIZE: u32 = 256;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum ClipContent {
+    Text(String),
+    /// images are kept as files next to the history, with a downscaled
+    /// thumbnail to list them
+    Image {
+        mime: String,
+        path: PathBuf,
+        thumb: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClipEntry {
+    /// hash of the copied data, identical copies are only kept once
+    pub hash: u64,
+    pub content: ClipContent,
+    /// unix timestamp in seconds
+    pub copied_at: u64,
+}
+
+impl ClipEntry {
+    /// the clip to put back on the clipboard
+    pub fn clip(&self) -> anyhow::Result<Clip> {
+        Ok(match &self.content {
+            ClipContent::Text(text) => Clip::text(text),
+            ClipContent::Image { mime, path, .. } => Clip {
+                mime: mime.clone(),
+                data: std::fs::read(path)?,
+            },
+        })
+    }
+}
+
+/// copied entries, newest first
+#[derive(Debug, Clone, Default, Deref, DerefMut, Deserialize, Serialize)]
+pub struct ClipHistory(Vec<ClipEntry>);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Loaded(ClipHistory),
+    Copied(Clip),
+    Stored(ClipEntry),
+}
+
+impl Message {
+    /// whether the history may have changed after this message
+    pub fn updates_history(&self) -> bool {
+        !matches!(self, Message::Copied(_))
+    }
+}
+
+/// records what's copied, persisted in the data dir
+pub struct ClipHistoryServ {
+    history: ClipHistory,
+}
+
+impl ClipHistoryServ {
+    pub fn history(&self) -> &ClipHistory {
+        &self.history
+    }
+
+    /// move the entry to the top, dropping the oldest past the cap
+    fn push(&mut self, entry: ClipEntry) -> Task<Message> {
+        self.history.retain(|old| old.hash != entry.hash);
+        self.history.insert(0, entry);
+
+        let max_entries = CONFIG.clipboard.max_entries.unwrap_or(MAX_ENTRIES);
+        let keep = max_entries.min(self.history.len());
+        let removed: Vec<_> = self
+            .history
+            .drain(keep..)
+            .flat_map(|entry| match entry.content {
+                ClipContent::Image { path, thumb, .. } => vec![path, thumb],
+                ClipContent::Text(_) => Vec::new(),
+            })
+            .collect();
+
+        let remove_images = Task::future(async move {
+            for path in removed {
+                if let Err(err) = fs::remove_file(&path).await {
+                    info!("Error removing clipboard image {path:?}: {err:?}");
+                }
+            }
+        })
+        .discard();
+
+        remove_images.chain(self.save())
+    }
+
+    fn save(&self) -> Task<Message> {
+        self.history
+            .jsonify()
+            .map(ClipHistory::save)
+            .map(Task::future)
+            .map(Task::discard)
+            .inspect_err(|err| {
+                info!("Error serializing clipboard history: {err:?}");
+            })
+            .unwrap_or(Task::none())
+    }
+}
+
+impl Service for ClipHistoryServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let init_history = Task::future(async {
+            ClipHistory::load()
+                .await
+                .inspect_err(|err| {
+                    info!("Error loading clipboard history: {err:?}");
+                })
+                .unwrap_or_default()
+        })
+        .map(Message::Loaded);
+
+        (
+            Self {
+                history: ClipHistory::default(),
+            },
+            init_history.map(f),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::run(|| data_control::watch(CONFIG.clipboard.max_bytes.unwrap_or(MAX_BYTES)))
+            .map(Message::Copied)
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::Loaded(history) => {
+                // keep what was copied while loading
+                let copied = std::mem::replace(&mut self.history, history);
+                copied
+                    .0
+                    .into_iter()
+                    .rev()
+                    .map(|entry| self.push(entry))
+                    .fold(Task::none(), Task::chain)
+            }
+            Message::Copied(clip) => Task::future(async {
+                store(clip)
+                    .await
+                    .inspect_err(|err| {
+                        info!("Error storing clip: {err:?}");
+                    })
+                    .ok()
+            })
+            .and_then(|entry| Task::done(Message::Stored(entry))),
+            Message::Stored(entry) => self.push(entry),
+        }
+    }
+}
+
+impl ClipHistory {
+    fn get_dir() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or(PathBuf::from("."))
+            .join("icedshell/clipboard")
+    }
+
+    fn get_path() -> PathBuf {
+        Self::get_dir().join("history.json")
+    }
+
+    async fn load() -> anyhow::Result<Self> {
+        let path = Self::get_path();
+
+        if !fs::try_exists(&path).await? {
+            return Ok(Self::default());
+        };
+
+        let file_str = fs::read_to_string(&path).await?;
+        Ok(serde_json::from_str(&file_str)?)
+    }
+
+    fn jsonify(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    async fn save(json_str: String) -> anyhow::Result<()> {
+        fs::create_dir_all(Self::get_dir()).await?;
+        fs::write(Self::get_path(), &json_str).await?;
+        Ok(())
+    }
+}
+
+/// turn the clip into an entry, writing images and their thumbnails out to
+/// the history dir
+async fn store(clip: Clip) -> anyhow::Result<ClipEntry> {
+    let mut hasher = DefaultHasher::new();
+    clip.data.hash(&mut hasher);
+    let hash = hasher.finish();
+    let copied_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let content = if clip.is_text() {
+        ClipContent::Text(String::from_utf8(clip.data)?)
+    } else {
+        let ext = clip.mime.rsplit('/').next().unwrap_or("img");
+        let dir = ClipHistory::get_dir();
+        let path = dir.join(format!("{hash:x}.{ext}"));
+        let thumb = dir.join(format!("{hash:x}.thumb.png"));
+        fs::create_dir_all(&dir).await?;
+
+        let data = clip.data;
+        let (data, thumb_data) = tokio::task::spawn_blocking(move || {
+            let thumb_data = thumbnail(&data);
+            (data, thumb_data)
+        })
+        .await?;
+        fs::write(&thumb, thumb_data?).await?;
+        fs::write(&path, &data).await?;
+
+        ClipContent::Image {
+            mime: clip.mime,
+            path,
+            thumb,
+        }
+    };
+
+    Ok(ClipEntry {
+        hash,
+        content,
+        copied_at,
+    })
+}
+
+/// the image downscaled to fit `THUMB_SIZE`, as png
+fn thumbnail(data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let thumb = image::load_from_memory(data)?.thumbnail(THUMB_SIZE, THUMB_SIZE);
+    let mut buf = Cursor::new(Vec::new());
+    thumb.write_to(&mut buf, image::ImageFormat::Png)?;
+    Ok(buf.into_inner())
+}
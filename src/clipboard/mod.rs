@@ -0,0 +1,2 @@
+pub mod data_control;
+pub mod history_serv;
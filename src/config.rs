@@ -0,0 +1,113 @@
+use std::{path::PathBuf, sync::LazyLock};
+
+use serde::Deserialize;
+use tracing::info;
+
+use crate::powermenu::action::{Confirm, PowerAction};
+
+/// user config from ~/.config/icedshell/config.json, read once on first use
+/// missing keys fall back to their defaults
+pub static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    Config::load()
+        .inspect_err(|err| {
+            info!("Error loading config: {err:?}");
+        })
+        .unwrap_or_default()
+});
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub launcher: LauncherConfig,
+    pub clipboard: ClipboardConfig,
+    pub powermenu: PowerMenuConfig,
+    pub lock: LockConfig,
+    pub idle: IdleConfig,
+    pub battery: BatteryConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LauncherConfig {
+    /// command to run terminal apps with, e.g. ["kitty", "-e"]
+    /// falls back to `$TERMINAL -e`, then xdg-terminal-exec
+    pub terminal: Option<Vec<String>>,
+    /// dirs the files modi searches, `~/` is expanded
+    /// defaults to the home dir
+    pub file_roots: Option<Vec<PathBuf>>,
+    /// type the picked emoji with wtype instead of copying it
+    pub emoji_type: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// entries kept in the history, defaults to 200
+    pub max_entries: Option<usize>,
+    /// larger copies aren't recorded, defaults to 5 MiB
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PowerMenuConfig {
+    /// buttons from left to right
+    /// defaults to lock, sleep, reboot, shutdown, shutdown in 30 minutes,
+    /// cancel shutdown and logout
+    pub actions: Option<Vec<PowerAction>>,
+    /// how actions marked `confirm` are confirmed, defaults to pressing again
+    pub confirm: Confirm,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LockConfig {
+    /// pam service the password is checked against, defaults to "login"
+    pub pam_service: Option<String>,
+}
+
+/// idle timeouts in seconds, 0 disables the stage
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct IdleConfig {
+    /// defaults to 300
+    pub dim_after: Option<u64>,
+    /// defaults to 600
+    pub lock_after: Option<u64>,
+    /// defaults to 1800
+    pub suspend_after: Option<u64>,
+    /// brightness kept while dimmed, in percent, defaults to 30
+    pub dim_percent: Option<u32>,
+}
+
+/// battery percentages that raise escalating warnings while discharging
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BatteryConfig {
+    /// the sysfs power supply to show, every battery combined when unset
+    pub name: Option<String>,
+    /// first warning, also where the bar turns red, defaults to 10
+    pub low: Option<f64>,
+    /// defaults to 5
+    pub critical: Option<f64>,
+    /// suspends below this, off when unset
+    pub suspend_at: Option<f64>,
+}
+
+impl Config {
+    fn get_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or(PathBuf::from("."))
+            .join("icedshell/config.json")
+    }
+
+    fn load() -> anyhow::Result<Self> {
+        let path = Self::get_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let file_str = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&file_str)?)
+    }
+}
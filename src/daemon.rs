@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::SystemTime};
 
 use derive_more::{Deref, DerefMut};
 use iced::{
@@ -21,12 +21,26 @@ use tracing::{debug, error as log_err, info};
 use crate::{
     Cli,
     bars::{delora_main, delora_sec, rena_main, rena_sec},
+    clipboard::history_serv::{self, ClipHistoryServ},
     feature::{Comp, FeatWindow, Feature, Service},
-    launcher,
+    idle::{self, IdleServ, caffeine_comp},
+    launcher::{
+        self,
+        app_index::{self, AppIndexServ},
+        file_index::{self, FileIndexServ},
+    },
+    lockscreen::{self, LockServ},
+    logind,
     niri::{self, monitors::MonitorsServ},
-    osd, powermenu, socket,
+    osd,
+    powermenu::{self, button_comp},
+    socket,
+    system_info::{
+        self,
+        battery_serv::{self, BatteryServ},
+    },
     theme::{self as mytheme},
-    tray::{TrayBar, TrayLayout, TrayMenuItemId, menu_comp as tray_menu},
+    tray::{TrayBar, TrayMenu, TrayMenuItemId, menu_comp as tray_menu, service as tray_serv},
 };
 
 #[derive(Debug, Clone)]
@@ -74,6 +88,13 @@ struct Features(HashMap<Id, Feat>);
 #[derive(Debug, Clone)]
 pub enum Message {
     NiriMon(niri::monitors::Message),
+    AppIndex(app_index::Message),
+    ClipHistory(history_serv::Message),
+    FileIndex(file_index::Message),
+    Lock(lockscreen::Message),
+    Idle(idle::Message),
+    Battery(battery_serv::Message),
+    ScheduledShutdown(Option<SystemTime>),
 
     Delora(Id, delora_main::Message),
     DeloraSec(Id, delora_sec::Message),
@@ -103,23 +124,56 @@ pub enum Message {
 struct Daemon {
     features: Features,
     mon_serv: niri::monitors::MonitorsServ,
+    app_index: AppIndexServ,
+    clip_history: ClipHistoryServ,
+    file_index: FileIndexServ,
+    lock: LockServ,
+    idle: IdleServ,
+    battery: BatteryServ,
+    /// when logind will shut down, if scheduled
+    scheduled_shutdown: Option<SystemTime>,
     tray_focused: bool,
     tray_close_handle: Option<Handle>,
+    /// the bar the open tray menu was opened from, each bar runs its own tray
+    /// service
+    tray_menu_bar: Option<Id>,
     host: Host,
 }
 
 impl Daemon {
     fn new(init: Init) -> (Self, Task<Message>) {
         let (mon_serv, mon_serv_task) = MonitorsServ::new((), Message::NiriMon);
+        let (app_index, app_index_task) = AppIndexServ::new((), Message::AppIndex);
+        let (clip_history, clip_history_task) = ClipHistoryServ::new((), Message::ClipHistory);
+        let (file_index, file_index_task) = FileIndexServ::new((), Message::FileIndex);
+        let (lock, lock_task) = LockServ::new((), Message::Lock);
+        let (idle, idle_task) = IdleServ::new((), Message::Idle);
+        let (battery, battery_task) = BatteryServ::new((), Message::Battery);
         (
             Self {
                 host: init.host,
                 features: Features(HashMap::new()),
                 mon_serv,
+                app_index,
+                clip_history,
+                file_index,
+                lock,
+                idle,
+                battery,
+                scheduled_shutdown: None,
                 tray_focused: false,
                 tray_close_handle: None,
+                tray_menu_bar: None,
             },
-            mon_serv_task,
+            Task::batch([
+                mon_serv_task,
+                app_index_task,
+                clip_history_task,
+                file_index_task,
+                lock_task,
+                idle_task,
+                battery_task,
+            ]),
         )
     }
 
@@ -131,6 +185,13 @@ impl Daemon {
         });
 
         let niri_mon = self.mon_serv.subscription().map(Message::NiriMon);
+        let app_index = self.app_index.subscription().map(Message::AppIndex);
+        let clip_history = self.clip_history.subscription().map(Message::ClipHistory);
+        let lock = self.lock.subscription().map(Message::Lock);
+        let idle = self.idle.subscription().map(Message::Idle);
+        let battery = self.battery.subscription().map(Message::Battery);
+        let scheduled_shutdown =
+            Subscription::run(logind::watch_scheduled_shutdown).map(Message::ScheduledShutdown);
 
         let socket_sub = Subscription::run(|| socket::listen().0).filter_map(|res| match res {
             Ok(request) => Some(Message::Socket(request)),
@@ -182,7 +243,17 @@ impl Daemon {
             })
             .collect();
 
-        let mut subs = vec![niri_mon, focus_subs, socket_sub];
+        let mut subs = vec![
+            niri_mon,
+            app_index,
+            clip_history,
+            lock,
+            idle,
+            battery,
+            scheduled_shutdown,
+            focus_subs,
+            socket_sub,
+        ];
         subs.append(&mut win_subs);
         Subscription::batch(subs)
     }
@@ -196,8 +267,11 @@ impl Daemon {
                         .map(move |m| Message::Delora(win_id, m));
 
                     let open_task = match message {
-                        delora_main::Message::OpenTrayMenu(name, layout) => {
-                            self.open_tray_menu(name, layout)
+                        delora_main::Message::OpenTrayMenu(name, menu) => {
+                            self.open_tray_menu(win_id, name, menu)
+                        }
+                        delora_main::Message::TrayService(message) => {
+                            self.update_tray_menu(win_id, message)
                         }
                         delora_main::Message::PowerButtonOnClicked => {
                             self.open_powermenu(powermenu::PowerArgs {
@@ -205,6 +279,9 @@ impl Daemon {
                                 no_focus: true,
                             })
                         }
+                        delora_main::Message::CaffeineOnClicked => {
+                            Task::done(Message::Idle(idle::Message::ToggleCaffeine))
+                        }
                         _ => Task::none(),
                     };
                     task.chain(open_task)
@@ -228,8 +305,11 @@ impl Daemon {
                         .map(move |m| Message::Rena(win_id, m));
 
                     let open_task = match message {
-                        rena_main::Message::OpenTrayMenu(name, layout) => {
-                            self.open_tray_menu(name, layout)
+                        rena_main::Message::OpenTrayMenu(name, menu) => {
+                            self.open_tray_menu(win_id, name, menu)
+                        }
+                        rena_main::Message::TrayService(message) => {
+                            self.update_tray_menu(win_id, message)
                         }
                         rena_main::Message::PowerButtonOnClicked => {
                             self.open_powermenu(powermenu::PowerArgs {
@@ -237,6 +317,9 @@ impl Daemon {
                                 no_focus: true,
                             })
                         }
+                        rena_main::Message::CaffeineOnClicked => {
+                            Task::done(Message::Idle(idle::Message::ToggleCaffeine))
+                        }
                         _ => Task::none(),
                     };
                     task.chain(open_task)
@@ -258,12 +341,13 @@ impl Daemon {
                         .update(message.clone())
                         .map_feat(win_id, Message::TrayMenu);
 
-                    let outer_task =
-                        if let tray_menu::Message::ItemSelected(name, menu_item_id) = message {
+                    let outer_task = match message {
+                        tray_menu::Message::ItemSelected(name, menu_item_id) => {
                             Task::done(Message::TrayMenuItemClicked(name, menu_item_id))
-                        } else {
-                            Task::none()
-                        };
+                        }
+                        tray_menu::Message::Close => Task::done(Message::RemoveWindow(win_id)),
+                        _ => Task::none(),
+                    };
 
                     inner_task.chain(outer_task)
                 } else {
@@ -281,6 +365,9 @@ impl Daemon {
                         | launcher::Message::ExecSuccess => {
                             Task::done(Message::RemoveWindow(win_id))
                         }
+                        launcher::Message::FileServ(
+                            launcher::file_serv::Message::ScanRequested,
+                        ) => Task::done(Message::FileIndex(file_index::Message::Rescan)),
                         _ => Task::none(),
                     };
 
@@ -317,6 +404,109 @@ impl Daemon {
                     Task::none()
                 }
             }
+            Message::AppIndex(message) => {
+                let updates_apps = message.updates_apps();
+                let inner_task = self.app_index.update(message).map(Message::AppIndex);
+                if !updates_apps {
+                    return inner_task;
+                }
+
+                // refresh open launchers
+                let apps = self.app_index.apps();
+                let launcher_tasks = self
+                    .features
+                    .iter()
+                    .filter(|(_, feat)| matches!(feat, Feat::Launcher(_)))
+                    .map(|(win_id, _)| {
+                        Task::done(Message::Launcher(
+                            *win_id,
+                            launcher::Message::AppsChanged(apps.clone()),
+                        ))
+                    });
+
+                Task::batch(std::iter::once(inner_task).chain(launcher_tasks))
+            }
+            Message::ClipHistory(message) => {
+                let updates_history = message.updates_history();
+                let inner_task = self.clip_history.update(message).map(Message::ClipHistory);
+                if !updates_history {
+                    return inner_task;
+                }
+
+                // refresh open launchers
+                let history = self.clip_history.history();
+                let launcher_tasks = self
+                    .features
+                    .iter()
+                    .filter(|(_, feat)| matches!(feat, Feat::Launcher(_)))
+                    .map(|(win_id, _)| {
+                        Task::done(Message::Launcher(
+                            *win_id,
+                            launcher::Message::ClipsChanged(history.clone()),
+                        ))
+                    });
+
+                Task::batch(std::iter::once(inner_task).chain(launcher_tasks))
+            }
+            Message::FileIndex(message) => {
+                let updates_files = message.updates_files();
+                let inner_task = self.file_index.update(message).map(Message::FileIndex);
+                if !updates_files {
+                    return inner_task;
+                }
+
+                // refresh open launchers
+                let Some(scan) = self.file_index.scan() else {
+                    return inner_task;
+                };
+                let launcher_tasks = self
+                    .features
+                    .iter()
+                    .filter(|(_, feat)| matches!(feat, Feat::Launcher(_)))
+                    .map(|(win_id, _)| {
+                        Task::done(Message::Launcher(
+                            *win_id,
+                            launcher::Message::FilesChanged(scan.clone()),
+                        ))
+                    });
+
+                Task::batch(std::iter::once(inner_task).chain(launcher_tasks))
+            }
+            Message::Lock(message) => {
+                let inner_task = self.lock.update(message.clone()).map(Message::Lock);
+                let out_task = match message {
+                    lockscreen::Message::Unlocked(Err(_)) => self.open_osd(osd::Modi::LockFailed),
+                    _ => Task::none(),
+                };
+                inner_task.chain(out_task)
+            }
+            Message::Idle(message) => {
+                let inner_task = self.idle.update(message.clone()).map(Message::Idle);
+                let out_task = match message {
+                    idle::Message::Lock => Task::done(Message::Lock(lockscreen::Message::Lock)),
+                    idle::Message::ToggleCaffeine => self.sync_caffeine(),
+                    _ => Task::none(),
+                };
+                inner_task.chain(out_task)
+            }
+            Message::Battery(message) => {
+                let inner_task = self.battery.update(message.clone()).map(Message::Battery);
+                let out_task = match message {
+                    battery_serv::Message::OnBat(_) => self.sync_battery(),
+                    battery_serv::Message::Warn(warning, cap) => {
+                        self.open_osd(osd::Modi::Battery(warning, cap))
+                    }
+                    _ => Task::none(),
+                };
+                inner_task.chain(out_task)
+            }
+            Message::ScheduledShutdown(scheduled) => {
+                if scheduled == self.scheduled_shutdown {
+                    return Task::none();
+                }
+                self.scheduled_shutdown = scheduled;
+                self.sync_scheduled_shutdown()
+            }
             Message::NiriMon(message) => {
                 let inner_task = self.mon_serv.update(message).map(Message::NiriMon);
                 let num_mon = self.mon_serv.len();
@@ -368,9 +558,13 @@ impl Daemon {
             }
 
             Message::Socket(req) => match req {
-                socket::Request::Launcher => self.open_launcher(),
-                socket::Request::Osd(args) => self.open_osd(args),
+                socket::Request::Launcher(args) => self.open_launcher(args),
+                socket::Request::Osd(args) => self.open_osd(args.into()),
                 socket::Request::PowerMenu(args) => self.open_powermenu(args),
+                socket::Request::Lock => Task::done(Message::Lock(lockscreen::Message::Lock)),
+                socket::Request::Caffeine => {
+                    Task::done(Message::Idle(idle::Message::ToggleCaffeine))
+                }
             },
 
             _ => Task::none(),
@@ -443,6 +637,9 @@ impl Daemon {
                 id: main_id,
             }))
             .chain(inner_task)
+            .chain(self.sync_caffeine())
+            .chain(self.sync_battery())
+            .chain(self.sync_scheduled_shutdown())
     }
 }
 
@@ -484,6 +681,9 @@ impl Daemon {
                 id: rena_id,
             }))
             .chain(inner_task)
+            .chain(self.sync_caffeine())
+            .chain(self.sync_battery())
+            .chain(self.sync_scheduled_shutdown())
     }
 
     fn open_rena_sec(&mut self, output_name: String) -> Task<Message> {
@@ -519,6 +719,7 @@ impl Daemon {
                 id: rena_id,
             }))
             .chain(inner_task)
+            .chain(self.sync_battery())
     }
 }
 
@@ -575,6 +776,81 @@ impl Daemon {
     }
 }
 
+// idle logic
+impl Daemon {
+    /// show the caffeine state on the bars
+    fn sync_caffeine(&self) -> Task<Message> {
+        let message = caffeine_comp::Message::Set(self.idle.is_caffeinated());
+        let bar_tasks = self
+            .features
+            .iter()
+            .filter_map(|(win_id, feat)| match feat {
+                Feat::Delora(_) => Some(Task::done(Message::Delora(
+                    *win_id,
+                    delora_main::Message::Caffeine(message.clone()),
+                ))),
+                Feat::Rena(_) => Some(Task::done(Message::Rena(
+                    *win_id,
+                    rena_main::Message::Caffeine(message.clone()),
+                ))),
+                _ => None,
+            });
+
+        Task::batch(bar_tasks)
+    }
+}
+
+// scheduled shutdown logic
+impl Daemon {
+    fn sync_scheduled_shutdown(&self) -> Task<Message> {
+        let message = button_comp::Message::Scheduled(self.scheduled_shutdown);
+        let bar_tasks = self
+            .features
+            .iter()
+            .filter_map(|(win_id, feat)| match feat {
+                Feat::Delora(_) => Some(Task::done(Message::Delora(
+                    *win_id,
+                    delora_main::Message::PowerBtn(message.clone()),
+                ))),
+                Feat::Rena(_) => Some(Task::done(Message::Rena(
+                    *win_id,
+                    rena_main::Message::PowerBtn(message.clone()),
+                ))),
+                _ => None,
+            });
+
+        Task::batch(bar_tasks)
+    }
+}
+
+// battery logic
+impl Daemon {
+    /// every bar shows the battery service's reading instead of polling its own
+    fn sync_battery(&self) -> Task<Message> {
+        let message = system_info::Message::OnBat(self.battery.info().clone());
+        let bar_tasks = self
+            .features
+            .iter()
+            .filter_map(|(win_id, feat)| match feat {
+                Feat::Delora(_) => Some(Task::done(Message::Delora(
+                    *win_id,
+                    delora_main::Message::SysInfo(message.clone()),
+                ))),
+                Feat::Rena(_) => Some(Task::done(Message::Rena(
+                    *win_id,
+                    rena_main::Message::SysInfo(message.clone()),
+                ))),
+                Feat::RenaSec(_) => Some(Task::done(Message::RenaSec(
+                    *win_id,
+                    rena_sec::Message::SysInfo(message.clone()),
+                ))),
+                _ => None,
+            });
+
+        Task::batch(bar_tasks)
+    }
+}
+
 // tray menu feature logic
 impl Daemon {
     fn handle_tray_click(&mut self, name: String, menu_item_id: TrayMenuItemId) -> Task<Message> {
@@ -584,11 +860,11 @@ impl Daemon {
                 let win_id = *win_id;
                 match feat {
                     Feat::Delora(bar) => Some(
-                        bar.tray_menu_item_clicked(name.clone(), menu_item_id.clone())
+                        bar.tray_menu_item_clicked(name.clone(), menu_item_id)
                             .map_feat(win_id, Message::Delora),
                     ),
                     Feat::Rena(bar) => Some(
-                        bar.tray_menu_item_clicked(name.clone(), menu_item_id.clone())
+                        bar.tray_menu_item_clicked(name.clone(), menu_item_id)
                             .map_feat(win_id, Message::Rena),
                     ),
                     _ => None,
@@ -617,7 +893,7 @@ impl Daemon {
 
         task
     }
-    fn open_tray_menu(&mut self, name: String, layout: TrayLayout) -> Task<Message> {
+    fn open_tray_menu(&mut self, bar_id: Id, name: String, menu: TrayMenu) -> Task<Message> {
         let remove = self
             .features
             .iter()
@@ -631,10 +907,11 @@ impl Daemon {
             .unwrap_or(Task::none());
 
         let (menu_feat, layer_settings, inner_task) =
-            tray_menu::MenuComp::open(tray_menu::Init { name, layout }, Message::TrayMenu);
+            tray_menu::MenuComp::open(tray_menu::Init { name, menu }, Message::TrayMenu);
         let win_id = menu_feat.id;
 
         self.features.insert(win_id, Feat::TrayMenu(menu_feat));
+        self.tray_menu_bar = Some(bar_id);
 
         debug!("opening tray menu window");
 
@@ -645,14 +922,45 @@ impl Daemon {
             }))
             .chain(inner_task)
     }
+
+    /// forward menu changes to the open tray menu, so it doesn't show stale items,
+    /// only from the bar it was opened from so they aren't applied once per bar
+    fn update_tray_menu(&self, bar_id: Id, message: tray_serv::Message) -> Task<Message> {
+        if self.tray_menu_bar != Some(bar_id) {
+            return Task::none();
+        }
+        let Some(win_id) = self
+            .features
+            .iter()
+            .find(|(_, feat)| matches!(feat, Feat::TrayMenu(_)))
+            .map(|(win_id, _)| *win_id)
+        else {
+            return Task::none();
+        };
+
+        let menu_message = match message {
+            tray_serv::Message::MenuLayoutChanged(name, layout) => {
+                tray_menu::Message::LayoutChanged(name, layout)
+            }
+            tray_serv::Message::MenuPropsChanged(name, updated, removed) => {
+                tray_menu::Message::PropsChanged(name, updated, removed)
+            }
+            _ => return Task::none(),
+        };
+        Task::done(Message::TrayMenu(win_id, menu_message))
+    }
 }
 
 // launcher window
 impl Daemon {
-    fn open_launcher(&mut self) -> Task<Message> {
+    fn open_launcher(&mut self, args: launcher::LauncherArgs) -> Task<Message> {
         let (launcher_feat, settings, inner_task) = launcher::Launcher::open(
             launcher::Init {
                 output: self.mon_serv.cur_monitor().cloned(),
+                args,
+                apps: self.app_index.apps().clone(),
+                clips: self.clip_history.history().clone(),
+                files: self.file_index.scan().cloned(),
             },
             Message::Launcher,
         );
@@ -696,11 +1004,11 @@ impl Daemon {
 
 /// osd logic
 impl Daemon {
-    fn open_osd(&mut self, args: osd::OsdCommand) -> Task<Message> {
+    fn open_osd(&mut self, modi: osd::Modi) -> Task<Message> {
         let (osd_feat, settings, inner_task) = osd::Osd::open(
             osd::Init {
                 monitor: self.mon_serv.cur_monitor().cloned(),
-                command: args,
+                modi,
             },
             Message::Osd,
         );
@@ -0,0 +1,55 @@
+//! screen dimming through the backlight, set by logind so no udev rule is needed
+use std::{fs, path::Path};
+
+use tracing::info;
+
+use crate::logind;
+
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+
+/// a backlight device and the brightness to restore it to
+#[derive(Debug, Clone)]
+pub struct Saved {
+    name: String,
+    brightness: u32,
+}
+
+fn read_u32(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// dim every backlight to `percent` of its current brightness
+pub async fn dim(percent: u32) -> Vec<Saved> {
+    let Ok(entries) = fs::read_dir(BACKLIGHT_DIR) else {
+        return Vec::new();
+    };
+
+    let mut saved = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(brightness) = read_u32(&entry.path().join("brightness")) else {
+            continue;
+        };
+
+        if set(&name, brightness * percent.min(100) / 100).await {
+            saved.push(Saved { name, brightness });
+        }
+    }
+    saved
+}
+
+pub async fn restore(saved: Vec<Saved>) {
+    for Saved { name, brightness } in saved {
+        set(&name, brightness).await;
+    }
+}
+
+async fn set(name: &str, brightness: u32) -> bool {
+    let res = match logind::session().await {
+        Ok(session) => session.set_brightness("backlight", name, brightness).await,
+        Err(err) => Err(err),
+    };
+
+    res.inspect_err(|err| info!("Error setting {name} brightness: {err:?}"))
+        .is_ok()
+}
@@ -0,0 +1,72 @@
+use iced::{
+    Length, Task, padding,
+    widget::{
+        Button,
+        button::{self, Status},
+        container,
+    },
+};
+
+use crate::{feature::Comp, theme::CAT_THEME, widget::align_center};
+
+/// shows whether idle actions are paused, the state lives in the daemon
+pub struct CaffeineButton {
+    is_on: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    OnClick,
+    Set(bool),
+}
+
+impl Comp for CaffeineButton {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: iced::advanced::graphics::futures::MaybeSend + 'static>(
+        _input: Self::Init,
+        _f: impl Fn(Self::Message) -> O + iced::advanced::graphics::futures::MaybeSend + 'static,
+    ) -> (Self, iced::Task<O>) {
+        (Self { is_on: false }, Task::none())
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        if let Message::Set(is_on) = message {
+            self.is_on = is_on;
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> iced::Element<'_, Self::Message> {
+        let theme = &CAT_THEME;
+        let spacing = theme.spacing();
+        let main_clr = theme.surface2();
+
+        let icon = lucide_icons::Icon::Coffee
+            .widget()
+            .color(if self.is_on {
+                theme.peach()
+            } else {
+                theme.overlay0()
+            })
+            .center()
+            .size(spacing.lg());
+
+        let icon = container(icon)
+            .center_y(Length::Fill)
+            .padding(padding::horizontal(spacing.sm()));
+
+        let button = Button::new(icon)
+            .style(move |_, status| button::Style {
+                background: match status {
+                    Status::Hovered | Status::Pressed => Some(theme.overlay2().into()),
+                    _ => Some(main_clr.into()),
+                },
+                ..Default::default()
+            })
+            .on_press(Message::OnClick);
+
+        align_center!(button).into()
+    }
+}
@@ -0,0 +1,245 @@
+//! dims, locks and then suspends after the configured idle timeouts
+//! held back by wayland and logind idle inhibitors, screencasts and caffeine
+mod backlight;
+pub mod caffeine_comp;
+mod notify;
+
+use std::{collections::HashSet, time::Duration};
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend, futures::Stream, time};
+use tracing::info;
+
+use crate::{
+    config::CONFIG,
+    feature::Service,
+    logind,
+    niri::stream::{self as niri_stream, NiriEvent},
+};
+
+const DIM_AFTER: u64 = 300;
+const LOCK_AFTER: u64 = 600;
+const SUSPEND_AFTER: u64 = 1800;
+const DIM_PERCENT: u32 = 30;
+/// logind doesn't signal inhibitor changes, so held back stages poll
+const RECHECK_EVERY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Dim,
+    Lock,
+    Suspend,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Idle(notify::IdleEvent),
+    /// whether a logind idle inhibitor holds the stage back
+    Checked(Stage, bool),
+    /// check the held back stages again
+    Recheck,
+    Dimmed(Vec<backlight::Saved>),
+    /// active screencast stream ids
+    CastsChanged(HashSet<u64>),
+    CastChanged(u64, bool),
+    ToggleCaffeine,
+    /// handled by the daemon
+    Lock,
+}
+
+pub struct IdleServ {
+    caffeine: bool,
+    casts: HashSet<u64>,
+    /// past their timeout without input since
+    idle: HashSet<Stage>,
+    /// ran since the stage went idle
+    done: HashSet<Stage>,
+    dimmed: Option<Vec<backlight::Saved>>,
+}
+
+impl IdleServ {
+    pub fn is_caffeinated(&self) -> bool {
+        self.caffeine
+    }
+
+    fn is_paused(&self) -> bool {
+        self.caffeine || !self.casts.is_empty()
+    }
+
+    /// idle stages that were held back
+    fn pending(&self) -> impl Iterator<Item = Stage> + '_ {
+        self.idle.difference(&self.done).copied()
+    }
+
+    /// check the logind idle inhibitors before running a pending stage
+    fn check(&self, stage: Stage) -> Task<Message> {
+        if self.is_paused() {
+            return Task::none();
+        }
+
+        Task::future(async move {
+            let is_inhibited = logind::is_idle_inhibited().await.unwrap_or_else(|err| {
+                info!("Error checking idle inhibitors: {err:?}");
+                false
+            });
+            Message::Checked(stage, is_inhibited)
+        })
+    }
+
+    fn check_pending(&self) -> Task<Message> {
+        Task::batch(self.pending().map(|stage| self.check(stage)))
+    }
+
+    fn undim(&mut self) -> Task<Message> {
+        match self.dimmed.take() {
+            Some(saved) => Task::future(backlight::restore(saved)).discard(),
+            None => Task::none(),
+        }
+    }
+}
+
+fn watch_idle() -> impl Stream<Item = notify::IdleEvent> {
+    let config = &CONFIG.idle;
+    let timeouts = [
+        (Stage::Dim, config.dim_after.unwrap_or(DIM_AFTER)),
+        (Stage::Lock, config.lock_after.unwrap_or(LOCK_AFTER)),
+        (
+            Stage::Suspend,
+            config.suspend_after.unwrap_or(SUSPEND_AFTER),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, secs)| *secs > 0)
+    .map(|(stage, secs)| (stage, Duration::from_secs(secs)))
+    .collect();
+
+    notify::watch(timeouts)
+}
+
+impl Service for IdleServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        Self {
+            caffeine: false,
+            casts: HashSet::new(),
+            idle: HashSet::new(),
+            done: HashSet::new(),
+            dimmed: None,
+        }
+        .to_tuple()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let idle_sub = Subscription::run(watch_idle).map(Message::Idle);
+
+        let casts_sub = Subscription::run(niri_stream::listen)
+            .filter_map(|res| res.ok())
+            .filter_map(|event| match event {
+                NiriEvent::CastsChanged { casts } => Some(Message::CastsChanged(
+                    casts
+                        .iter()
+                        .filter(|cast| cast.is_active)
+                        .map(|cast| cast.stream_id)
+                        .collect(),
+                )),
+                NiriEvent::CastStartedOrChanged { cast } => {
+                    Some(Message::CastChanged(cast.stream_id, cast.is_active))
+                }
+                NiriEvent::CastStopped { stream_id } => {
+                    Some(Message::CastChanged(stream_id, false))
+                }
+                _ => None,
+            });
+
+        let recheck_sub = if self.pending().next().is_some() {
+            time::every(RECHECK_EVERY).map(|_| Message::Recheck)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([idle_sub, casts_sub, recheck_sub])
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::Idle(notify::IdleEvent { stage, is_idle }) => {
+                if !is_idle {
+                    self.idle.remove(&stage);
+                    self.done.remove(&stage);
+                    return match stage {
+                        Stage::Dim => self.undim(),
+                        _ => Task::none(),
+                    };
+                }
+
+                self.idle.insert(stage);
+                self.check(stage)
+            }
+            // held back, rechecked until input or the inhibitor goes away
+            Message::Checked(_, true) => Task::none(),
+            Message::Checked(stage, false) => {
+                // input came back during the check, or it already ran
+                let is_pending = self.idle.contains(&stage) && !self.done.contains(&stage);
+                if !is_pending || self.is_paused() {
+                    return Task::none();
+                }
+                self.done.insert(stage);
+
+                match stage {
+                    Stage::Dim => Task::future(backlight::dim(
+                        CONFIG.idle.dim_percent.unwrap_or(DIM_PERCENT),
+                    ))
+                    .map(Message::Dimmed),
+                    Stage::Lock => Task::done(Message::Lock),
+                    Stage::Suspend => Task::future(async {
+                        let res = match logind::manager().await {
+                            Ok(manager) => manager.suspend(false).await,
+                            Err(err) => Err(err),
+                        };
+                        if let Err(err) = res {
+                            info!("Error suspending: {err:?}");
+                        }
+                    })
+                    .discard(),
+                }
+            }
+            Message::Recheck => self.check_pending(),
+            Message::Dimmed(saved) => {
+                self.dimmed = Some(saved);
+                // input came back while dimming
+                if self.idle.contains(&Stage::Dim) {
+                    Task::none()
+                } else {
+                    self.undim()
+                }
+            }
+            Message::CastsChanged(casts) => {
+                self.casts = casts;
+                self.check_pending()
+            }
+            Message::CastChanged(stream_id, is_active) => {
+                if is_active {
+                    self.casts.insert(stream_id);
+                } else {
+                    self.casts.remove(&stream_id);
+                }
+                self.check_pending()
+            }
+            Message::ToggleCaffeine => {
+                self.caffeine = !self.caffeine;
+                info!("caffeine: {}", self.caffeine);
+                if self.caffeine {
+                    self.done.remove(&Stage::Dim);
+                    self.undim()
+                } else {
+                    self.check_pending()
+                }
+            }
+            Message::Lock => Task::none(),
+        }
+    }
+}
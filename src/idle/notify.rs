@@ -0,0 +1,99 @@
+//! idle timeouts through ext-idle-notify-v1, the compositor holds them back
+//! while a wayland idle inhibitor is active, e.g. a playing video
+use std::{thread, time::Duration};
+
+use iced::futures::{Stream, channel::mpsc};
+use tracing::info;
+use wayland_client::{
+    Connection, Dispatch, QueueHandle, delegate_noop,
+    globals::{GlobalListContents, registry_queue_init},
+    protocol::{wl_registry::WlRegistry, wl_seat::WlSeat},
+};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+
+use crate::idle::Stage;
+
+#[derive(Debug, Clone, Copy)]
+pub struct IdleEvent {
+    pub stage: Stage,
+    /// false once there's input again
+    pub is_idle: bool,
+}
+
+struct State {
+    tx: mpsc::UnboundedSender<IdleEvent>,
+}
+
+/// stream idle and resume events for each stage after its timeout
+pub fn watch(timeouts: Vec<(Stage, Duration)>) -> impl Stream<Item = IdleEvent> {
+    let (tx, rx) = mpsc::unbounded();
+
+    thread::spawn(move || {
+        let res = (|| -> anyhow::Result<()> {
+            let conn = Connection::connect_to_env()?;
+            let (globals, mut queue) = registry_queue_init::<State>(&conn)?;
+            let qh = queue.handle();
+            let seat: WlSeat = globals.bind(&qh, 1..=1, ())?;
+            let notifier: ExtIdleNotifierV1 = globals.bind(&qh, 1..=1, ())?;
+
+            let _notifications: Vec<_> = timeouts
+                .into_iter()
+                .map(|(stage, timeout)| {
+                    let ms = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+                    notifier.get_idle_notification(ms, &seat, &qh, stage)
+                })
+                .collect();
+
+            let mut state = State { tx };
+            while !state.tx.is_closed() {
+                queue.blocking_dispatch(&mut state)?;
+            }
+            Ok(())
+        })();
+
+        if let Err(err) = res {
+            info!("Error watching idle: {err:?}");
+        }
+    });
+
+    rx
+}
+
+impl Dispatch<ExtIdleNotificationV1, Stage> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        stage: &Stage,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let is_idle = match event {
+            ext_idle_notification_v1::Event::Idled => true,
+            ext_idle_notification_v1::Event::Resumed => false,
+            _ => return,
+        };
+        let _ = state.tx.unbounded_send(IdleEvent {
+            stage: *stage,
+            is_idle,
+        });
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlRegistry,
+        _event: <WlRegistry as wayland_client::Proxy>::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(State: ignore WlSeat);
+delegate_noop!(State: ExtIdleNotifierV1);
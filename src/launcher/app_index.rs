@@ -0,0 +1,448 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    ffi::{OsStr, OsString},
+    future::ready,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use derive_more::{Deref, DerefMut};
+use iced::{
+    Subscription, Task,
+    advanced::graphics::futures::MaybeSend,
+    futures::{Stream, StreamExt, stream},
+};
+use inotify::{Inotify, WatchMask};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::info;
+
+use crate::{
+    feature::Service,
+    launcher::app_serv::{
+        AppDesc, AppNameToAppMap, get_bin_dirs, get_data_dirs, get_raw_data_dirs, parse_app,
+    },
+};
+
+/// a parsed desktop file, files that aren't visible apps are kept so they
+/// aren't parsed again
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IndexEntry {
+    #[serde(flatten)]
+    key: FileKey,
+    app: Option<AppDesc>,
+}
+
+/// identifies a desktop file's content, the mtime alone never changes on nix
+/// where every store path has an mtime of 1, but a new version has a new
+/// target path
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FileKey {
+    /// the file with every symlink resolved
+    target: PathBuf,
+    size: u64,
+    /// unix timestamp in seconds
+    mtime: u64,
+}
+
+/// desktop files by path, cached in the cache dir
+#[derive(Debug, Clone, Default, Deref, DerefMut, Deserialize, Serialize)]
+pub struct DesktopFiles(BTreeMap<PathBuf, IndexEntry>);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    CacheLoaded(DesktopFiles),
+    Scanned(DesktopFiles),
+    FilesChanged(Vec<PathBuf>),
+    /// a symlink the data dirs resolve through changed, e.g. a nix profile switch
+    DataDirsChanged,
+    FilesUpdated(Vec<(PathBuf, Option<IndexEntry>)>),
+}
+
+impl Message {
+    /// whether the apps may have changed after this message
+    pub fn updates_apps(&self) -> bool {
+        !matches!(self, Message::FilesChanged(_) | Message::DataDirsChanged)
+    }
+}
+
+/// index of the desktop apps shared by every launcher
+/// loaded from the cache, then only files that changed are parsed again
+pub struct AppIndexServ {
+    /// in ascending priority
+    data_dirs: Vec<PathBuf>,
+    files: DesktopFiles,
+    apps: AppNameToAppMap,
+}
+
+impl AppIndexServ {
+    pub fn apps(&self) -> &AppNameToAppMap {
+        &self.apps
+    }
+
+    /// resolve the apps from the files, higher priority data dirs override
+    /// apps with the same id, even when the override is hidden
+    fn rebuild(&mut self) {
+        let mut apps = AppNameToAppMap::default();
+
+        for data_dir in self.data_dirs.iter() {
+            for (path, entry) in self
+                .files
+                .iter()
+                .filter(|(path, _)| path.parent() == Some(data_dir))
+            {
+                match &entry.app {
+                    Some(app) => {
+                        apps.insert(app.app_id.clone(), app.clone());
+                    }
+                    None => {
+                        if let Some(app_id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                            apps.remove(app_id);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.apps = apps;
+    }
+
+    fn save(&self) -> Task<Message> {
+        self.files
+            .jsonify()
+            .map(DesktopFiles::save)
+            .map(Task::future)
+            .map(Task::discard)
+            .inspect_err(|err| {
+                info!("Error serializing app index: {err:?}");
+            })
+            .unwrap_or(Task::none())
+    }
+}
+
+impl Service for AppIndexServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let data_dirs = get_data_dirs()
+            .inspect_err(|err| {
+                info!("Error getting data dirs: {err:?}");
+            })
+            .unwrap_or_default();
+
+        let init_cache = Task::future(async {
+            DesktopFiles::load()
+                .await
+                .inspect_err(|err| {
+                    info!("Error loading app index: {err:?}");
+                })
+                .unwrap_or_default()
+        })
+        .map(Message::CacheLoaded);
+
+        (
+            Self {
+                data_dirs,
+                files: DesktopFiles::default(),
+                apps: AppNameToAppMap::default(),
+            },
+            init_cache.map(f),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        // restarts with watches on the new dirs when they resolve elsewhere
+        Subscription::run_with(WatchData(self.data_dirs.clone()), watch_data_dirs)
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::CacheLoaded(files) => {
+                self.files = files;
+                self.rebuild();
+
+                Task::future(scan(self.data_dirs.clone(), self.files.clone())).map(Message::Scanned)
+            }
+            Message::Scanned(files) => {
+                self.files = files;
+                self.rebuild();
+                self.save()
+            }
+            Message::FilesChanged(paths) => {
+                Task::future(index_files(paths)).map(Message::FilesUpdated)
+            }
+            Message::DataDirsChanged => {
+                let data_dirs = get_data_dirs()
+                    .inspect_err(|err| {
+                        info!("Error getting data dirs: {err:?}");
+                    })
+                    .unwrap_or_default();
+                if data_dirs == self.data_dirs {
+                    return Task::none();
+                }
+                self.data_dirs = data_dirs;
+
+                Task::future(scan(self.data_dirs.clone(), self.files.clone())).map(Message::Scanned)
+            }
+            Message::FilesUpdated(updates) => {
+                for (path, entry) in updates {
+                    match entry {
+                        Some(entry) => {
+                            self.files.insert(path, entry);
+                        }
+                        None => {
+                            self.files.remove(&path);
+                        }
+                    }
+                }
+                self.rebuild();
+                self.save()
+            }
+        }
+    }
+}
+
+impl DesktopFiles {
+    fn get_path() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or(PathBuf::from("."))
+            .join("icedshell/app_index.json")
+    }
+
+    async fn load() -> anyhow::Result<Self> {
+        let path = Self::get_path();
+
+        if !fs::try_exists(&path).await? {
+            return Ok(Self::default());
+        };
+
+        let file_str = fs::read_to_string(&path).await?;
+        let mut files: Self = serde_json::from_str(&file_str)?;
+        for app in files.values_mut().filter_map(|entry| entry.app.as_mut()) {
+            app.restore_icons();
+        }
+
+        Ok(files)
+    }
+
+    fn jsonify(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    async fn save(json_str: String) -> anyhow::Result<()> {
+        let path = Self::get_path();
+        if let Some(basename) = path.parent() {
+            fs::create_dir_all(basename).await?;
+        }
+
+        fs::write(&path, &json_str).await?;
+        Ok(())
+    }
+}
+
+fn is_desktop_file(path: &Path) -> bool {
+    path.extension() == Some(OsStr::new("desktop"))
+}
+
+async fn get_key(path: &Path) -> Option<FileKey> {
+    let target = fs::canonicalize(path).await.ok()?;
+    let meta = fs::metadata(&target).await.ok()?;
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+
+    Some(FileKey {
+        target,
+        size: meta.len(),
+        mtime,
+    })
+}
+
+async fn index_file(path: &Path, key: FileKey, bin_dirs: &BTreeSet<PathBuf>) -> IndexEntry {
+    let app = parse_app(path, bin_dirs)
+        .await
+        .inspect_err(|err| {
+            info!("Error parsing {path:?}: {err:?}");
+        })
+        .ok()
+        .flatten();
+
+    IndexEntry { key, app }
+}
+
+/// list the desktop files of the data dirs, only parsing those not in the cache
+/// or changed since
+async fn scan(data_dirs: Vec<PathBuf>, mut cached: DesktopFiles) -> DesktopFiles {
+    let bin_dirs = get_bin_dirs().unwrap_or_default();
+    let mut files = DesktopFiles::default();
+
+    for data_dir in data_dirs {
+        let Ok(mut entries) = fs::read_dir(&data_dir).await else {
+            continue;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if !is_desktop_file(&path) {
+                continue;
+            }
+            let Some(key) = get_key(&path).await else {
+                continue;
+            };
+
+            let entry = match cached.remove(&path) {
+                Some(entry) if entry.key == key => entry,
+                _ => index_file(&path, key, &bin_dirs).await,
+            };
+            files.insert(path, entry);
+        }
+    }
+
+    files
+}
+
+/// parse changed desktop files again, None for removed files
+async fn index_files(paths: Vec<PathBuf>) -> Vec<(PathBuf, Option<IndexEntry>)> {
+    let bin_dirs = get_bin_dirs().unwrap_or_default();
+    let mut updates = Vec::new();
+
+    for path in paths.into_iter().unique() {
+        let entry = match get_key(&path).await {
+            Some(key) => Some(index_file(&path, key, &bin_dirs).await),
+            None => None,
+        };
+        updates.push((path, entry));
+    }
+
+    updates
+}
+
+/// the dirs holding each symlink a path resolves through, with the link names
+/// e.g. `~/.nix-profile` and the nix profile generation links
+fn link_dirs(path: &Path) -> Vec<(PathBuf, OsString)> {
+    let mut links = Vec::new();
+    let mut pending = Some(path.to_owned());
+
+    // bounded in case of a symlink loop
+    while let Some(path) = pending.take()
+        && links.len() < 32
+    {
+        // outermost first, so the parents are already resolved
+        let ancestors: Vec<_> = path.ancestors().collect();
+        for ancestor in ancestors.into_iter().rev() {
+            let is_link =
+                std::fs::symlink_metadata(ancestor).is_ok_and(|meta| meta.file_type().is_symlink());
+            if !is_link {
+                continue;
+            }
+            let (Some(parent), Some(name), Ok(target)) = (
+                ancestor.parent(),
+                ancestor.file_name(),
+                std::fs::read_link(ancestor),
+            ) else {
+                break;
+            };
+
+            links.push((parent.to_owned(), name.to_owned()));
+            let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            pending = Some(parent.join(target).join(rest));
+            break;
+        }
+    }
+
+    links
+}
+
+/// the resolved data dirs
+#[derive(Hash)]
+struct WatchData(Vec<PathBuf>);
+
+enum Change {
+    File(PathBuf),
+    DataDirs,
+}
+
+/// stream changes of the desktop files in the data dirs, and of the symlinks
+/// the data dirs resolve through
+fn watch_data_dirs(WatchData(data_dirs): &WatchData) -> impl Stream<Item = Message> + use<> {
+    let data_dirs = data_dirs.clone();
+    let watch = move || -> anyhow::Result<_> {
+        let inotify = Inotify::init()?;
+        let mask = WatchMask::CREATE
+            | WatchMask::DELETE
+            | WatchMask::CLOSE_WRITE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO;
+
+        let mut dirs = HashMap::new();
+        for data_dir in data_dirs {
+            let wd = inotify.watches().add(&data_dir, mask)?;
+            dirs.insert(wd, data_dir);
+        }
+
+        let mut links: HashMap<_, HashSet<OsString>> = HashMap::new();
+        for (link_dir, name) in get_raw_data_dirs()?
+            .iter()
+            .flat_map(|raw_dir| link_dirs(raw_dir))
+        {
+            match inotify.watches().add(&link_dir, mask) {
+                Ok(wd) => {
+                    links.entry(wd).or_default().insert(name);
+                }
+                Err(err) => info!("Error watching {link_dir:?}: {err:?}"),
+            }
+        }
+
+        Ok((dirs, links, inotify.into_event_stream([0; 4096])?))
+    };
+
+    match watch() {
+        Ok((dirs, links, events)) => events
+            .filter_map(move |event| {
+                let change = event.ok().and_then(|event| {
+                    let name = event.name?;
+                    if links
+                        .get(&event.wd)
+                        .is_some_and(|names| names.contains(&name))
+                    {
+                        return Some(Change::DataDirs);
+                    }
+                    Some(dirs.get(&event.wd)?.join(name))
+                        .filter(|path| is_desktop_file(path))
+                        .map(Change::File)
+                });
+                ready(change)
+            })
+            .ready_chunks(64)
+            .map(|changes| {
+                if changes
+                    .iter()
+                    .any(|change| matches!(change, Change::DataDirs))
+                {
+                    return Message::DataDirsChanged;
+                }
+                Message::FilesChanged(
+                    changes
+                        .into_iter()
+                        .filter_map(|change| match change {
+                            Change::File(path) => Some(path),
+                            Change::DataDirs => None,
+                        })
+                        .collect(),
+                )
+            })
+            .boxed(),
+        Err(err) => {
+            info!("Error watching data dirs: {err:?}");
+            stream::empty().boxed()
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use std::{
     cmp,
-    collections::{BTreeMap, BTreeSet, HashMap},
-    env::VarError,
-    ffi::OsStr,
+    collections::{BTreeMap, BTreeSet},
+    env::{self, VarError},
     ops::Mul,
     path::{Path, PathBuf},
+    sync::LazyLock,
 };
 
 use derive_more::{Constructor, Deref, DerefMut, From};
@@ -16,26 +16,84 @@ use nucleo_matcher::{
     pattern::{CaseMatching, Normalization, Pattern},
 };
 use serde::{Deserialize, Serialize};
-use tokio::fs;
 use tracing::info;
 
 use crate::{
     feature::Service,
-    launcher::modi::{Modi, Query, Res},
+    launcher::{
+        desktop_exec::{self, FieldCodes},
+        frecency::{self, FrecencyMap},
+        modi::{self, Modi, Query, Res},
+    },
     widget::fdo_icons::{self, FdIcon},
 };
 
+const COUNTS_FILE: &str = "launcher_counts.json";
+
+/// locale suffixes to look up localized keys with, most specific first
+static LOCALE_KEYS: LazyLock<Vec<String>> = LazyLock::new(get_locale_keys);
+
+/// desktops to check OnlyShowIn and NotShowIn against
+static CURRENT_DESKTOPS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    env::var("XDG_CURRENT_DESKTOP")
+        .map(|desktops| desktops.split(":").map(str::to_owned).collect())
+        .unwrap_or(vec!["niri".to_owned()])
+});
+
 #[allow(clippy::too_many_arguments)]
-#[derive(Debug, Clone, Constructor)]
+#[derive(Debug, Clone, Constructor, Deserialize, Serialize)]
 pub struct AppDesc {
     pub name: String,
-    pub count: usize,
+    /// frecency score of launching the app
+    #[serde(skip)]
+    pub frecency: usize,
     pub app_id: String,
-    pub exec: String,
+    /// argv with field codes expanded
+    pub exec: Vec<String>,
+    /// working dir
+    pub path: Option<PathBuf>,
+    pub terminal: bool,
     pub gen_name: Option<String>,
     pub comment: Option<String>,
+    pub icon_path: Option<PathBuf>,
+    #[serde(skip)]
     pub icon: Option<FdIcon>,
     pub categories: Option<Vec<String>>,
+    pub keywords: Option<Vec<String>>,
+    pub actions: Vec<AppAction>,
+}
+
+/// a `[Desktop Action id]` group of an app
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AppAction {
+    pub id: String,
+    pub name: String,
+    /// argv with field codes expanded
+    pub exec: Vec<String>,
+    pub icon_path: Option<PathBuf>,
+    #[serde(skip)]
+    pub icon: Option<FdIcon>,
+}
+
+impl AppDesc {
+    /// rebuild the icons skipped when serializing
+    pub fn restore_icons(&mut self) {
+        self.icon = self.icon_path.clone().map(fdo_icons::from_path);
+        for action in self.actions.iter_mut() {
+            action.icon = action.icon_path.clone().map(fdo_icons::from_path);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppEntryId {
+    App(String),
+    Action(
+        /// app id
+        String,
+        /// action id
+        String,
+    ),
 }
 
 #[derive(Debug, Deref, DerefMut, From, Clone, Default)]
@@ -44,52 +102,50 @@ pub struct AppNameToAppMap(BTreeMap<String, AppDesc>);
 #[derive(Debug, Clone)]
 pub enum Message {
     LoadApps(AppNameToAppMap),
-    LoadCache(CountCache),
+    LoadCache(FrecencyMap),
     Query(Query),
 }
 
 pub struct AppServ {
-    count_cache: CountCache,
+    count_cache: FrecencyMap,
     apps: AppNameToAppMap,
+    /// app with its actions listed below it
+    expanded: Option<String>,
     last_query: Query,
-    res: Vec<Res<String>>,
+    res: Vec<Res<AppEntryId>>,
+    /// num of results across all pages
+    total: usize,
 }
 
 impl Service for AppServ {
     type Message = Message;
-    type Init = ();
+    /// the apps from the index
+    type Init = AppNameToAppMap;
 
     fn new<O: MaybeSend + 'static>(
-        _input: Self::Init,
+        apps: Self::Init,
         f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
     ) -> (Self, Task<O>) {
-        let init_apps = Task::future(async {
-            get_apps()
-                .await
-                .map(Message::LoadApps)
-                .inspect_err(|err| {
-                    info!("Error loading apps: {err:?}");
-                })
-                .unwrap_or(Message::LoadApps(AppNameToAppMap::default()))
-        });
         let init_cache = Task::future(async {
-            CountCache::load()
+            FrecencyMap::load(FrecencyMap::get_path(COUNTS_FILE))
                 .await
                 .inspect_err(|err| {
                     info!("Error loading cache: {err:?}");
                 })
                 .map(Message::LoadCache)
-                .unwrap_or(Message::LoadCache(CountCache::default()))
+                .unwrap_or(Message::LoadCache(FrecencyMap::default()))
         });
-        (
-            Self {
-                count_cache: CountCache::default(),
-                apps: AppNameToAppMap::default(),
-                res: Vec::new(),
-                last_query: Query::default(),
-            },
-            Task::batch([init_apps, init_cache]).map(f),
-        )
+        let mut app_serv = Self {
+            count_cache: FrecencyMap::default(),
+            apps,
+            expanded: None,
+            res: Vec::new(),
+            total: 0,
+            last_query: Query::default(),
+        };
+        let init_query = app_serv.query(Query::new(None, 0, 10));
+
+        (app_serv, Task::batch([init_query, init_cache]).map(f))
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
@@ -100,22 +156,13 @@ impl Service for AppServ {
         match message {
             Message::LoadApps(apps) => {
                 self.apps = apps;
-                for (app_id, count) in self.count_cache.iter() {
-                    self.apps.entry(app_id.clone()).and_modify(move |app| {
-                        app.count = *count;
-                    });
-                }
+                self.apply_frecency();
 
-                Task::done(Message::Query(Query::new(None, 0, 10)))
+                self.query(self.last_query.clone())
             }
             Message::LoadCache(cache) => {
                 self.count_cache = cache;
-
-                for (app_id, count) in self.count_cache.iter() {
-                    self.apps.entry(app_id.clone()).and_modify(move |app| {
-                        app.count = *count;
-                    });
-                }
+                self.apply_frecency();
 
                 Task::done(Message::Query(Query::new(None, 0, 10)))
             }
@@ -127,58 +174,135 @@ impl Service for AppServ {
     }
 }
 
+impl AppServ {
+    fn apply_frecency(&mut self) {
+        for (app_id, app) in self.apps.iter_mut() {
+            app.frecency = self.count_cache.score(app_id);
+        }
+    }
+
+    /// the app of the nth result, with the action if it's one of its actions
+    pub fn get_nth(&self, nth: usize) -> Option<(&AppDesc, Option<&AppAction>)> {
+        match &self.res.get(nth)?.id {
+            AppEntryId::App(app_id) => Some((self.apps.get(app_id)?, None)),
+            AppEntryId::Action(app_id, action_id) => {
+                let app = self.apps.get(app_id)?;
+                let action = app.actions.iter().find(|action| &action.id == action_id)?;
+                Some((app, Some(action)))
+            }
+        }
+    }
+
+    /// list the actions of the nth result below it
+    /// returns false if it has no actions
+    pub fn expand(&mut self, nth: usize) -> bool {
+        let Some(AppEntryId::App(app_id)) = self.res.get(nth).map(|res| &res.id) else {
+            return false;
+        };
+        if self
+            .apps
+            .get(app_id)
+            .is_none_or(|app| app.actions.is_empty())
+        {
+            return false;
+        }
+
+        self.expanded = Some(app_id.clone());
+        let _ = self.query(self.last_query.clone());
+        true
+    }
+
+    /// hide the listed actions
+    /// returns the new position of the expanded app, or None if nothing was expanded
+    pub fn collapse(&mut self) -> Option<usize> {
+        let app_id = self.expanded.take()?;
+        let _ = self.query(self.last_query.clone());
+
+        let app_pos = self
+            .res
+            .iter()
+            .position(|res| res.id == AppEntryId::App(app_id.clone()));
+        Some(app_pos.unwrap_or_default())
+    }
+}
+
 impl Modi for AppServ {
-    type Id = String;
+    type Id = AppEntryId;
     type Message = Message;
 
     fn len(&self) -> usize {
         self.res.len()
     }
 
+    fn total(&self) -> usize {
+        self.total
+    }
+
     fn res(&self) -> &Vec<super::modi::Res<Self::Id>> {
         &self.res
     }
 
-    fn query(&mut self, Query { term, page, limit }: Query) -> Task<Self::Message> {
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+        let Query { term, page, limit } = query;
         let mut apps: Vec<_> = self.apps.values().collect();
 
         if let Some(term) = term {
             apps = match_list(term, apps);
         } else {
-            apps.sort_by_key(|app| cmp::Reverse(app.count));
+            apps.sort_by_key(|app| cmp::Reverse(app.frecency));
         }
 
-        self.res = apps
+        // expanded first, so the listed actions count towards the page
+        let rows: Vec<_> = apps
             .into_iter()
-            .skip(page * limit)
-            .take(limit)
-            .map(Res::from)
+            .flat_map(|app| {
+                let actions = self
+                    .expanded
+                    .as_ref()
+                    .filter(|app_id| *app_id == &app.app_id)
+                    .map(|_| app.actions.iter().map(|action| action_res(app, action)))
+                    .into_iter()
+                    .flatten();
+
+                std::iter::once(Res::from(app)).chain(actions)
+            })
             .collect();
+
+        self.total = rows.len();
+        self.res = rows.into_iter().skip(page * limit).take(limit).collect();
         Task::none()
     }
 
-    fn exec(&mut self, app_id: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
-        let app = self.apps.get(app_id);
-        if let Some(app) = app {
-            let exec = app.exec.to_owned();
-            let mut parts = exec.split_whitespace();
-            if let Some(cmd) = parts.next() {
-                tokio::process::Command::new(cmd)
-                    .args(parts)
-                    .process_group(0)
-                    .spawn()?;
-                self.count_cache.on_app_exec(app_id);
-
-                return self
-                    .count_cache
-                    .jsonify()
-                    .map(CountCache::save)
-                    .map(Task::future)
-                    .map(Task::discard);
-            }
-        }
+    fn exec(&mut self, id: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        let (app_id, action_id) = match id {
+            AppEntryId::App(app_id) => (app_id, None),
+            AppEntryId::Action(app_id, action_id) => (app_id, Some(action_id)),
+        };
+        let Some(app) = self.apps.get(app_id) else {
+            return Ok(Task::none());
+        };
+        let exec = match action_id {
+            Some(action_id) => app
+                .actions
+                .iter()
+                .find(|action| &action.id == action_id)
+                .map(|action| &action.exec),
+            None => Some(&app.exec),
+        };
+        let Some(exec) = exec else {
+            return Ok(Task::none());
+        };
+
+        modi::spawn(exec, app.path.as_deref(), app.terminal)?;
+        self.count_cache.on_exec(app_id);
+        self.apply_frecency();
 
-        Ok(Task::none())
+        self.count_cache
+            .jsonify()
+            .map(|json_str| FrecencyMap::save(FrecencyMap::get_path(COUNTS_FILE), json_str))
+            .map(Task::future)
+            .map(Task::discard)
     }
 }
 
@@ -218,14 +342,39 @@ fn match_list(query: String, items: Vec<&AppDesc>) -> Vec<&AppDesc> {
                         .map(|(_, score)| score.mul(100))
                 })
             };
-            name_score.or(gen_name_score).or(cat_score).map(|_| {
-                let score = name_score
-                    .unwrap_or_default()
-                    .max(gen_name_score.unwrap_or_default())
-                    .max(cat_score.unwrap_or_default());
-
-                (app, score)
-            })
+            let keyword_score = {
+                app.keywords.as_ref().and_then(|keywords| {
+                    pattern
+                        .match_list(keywords, &mut matcher)
+                        .iter()
+                        .max_by_key(|(_, score)| *score)
+                        .map(|(_, score)| score.mul(80))
+                })
+            };
+            let comment_score = {
+                app.comment.as_ref().and_then(|comment| {
+                    let haystack = Utf32Str::new(comment, &mut buff);
+                    pattern
+                        .score(haystack, &mut matcher)
+                        .map(|score| score.mul(60))
+                })
+            };
+            name_score
+                .or(gen_name_score)
+                .or(cat_score)
+                .or(keyword_score)
+                .or(comment_score)
+                .map(|_| {
+                    let score = name_score
+                        .unwrap_or_default()
+                        .max(gen_name_score.unwrap_or_default())
+                        .max(cat_score.unwrap_or_default())
+                        .max(keyword_score.unwrap_or_default())
+                        .max(comment_score.unwrap_or_default())
+                        + frecency::match_bonus(app.frecency);
+
+                    (app, score)
+                })
         })
         .sorted_by_key(|(_, score)| cmp::Reverse(*score))
         .map(|(app, _)| app)
@@ -233,7 +382,7 @@ fn match_list(query: String, items: Vec<&AppDesc>) -> Vec<&AppDesc> {
 }
 
 /// get the binary paths from &PATHS as a set
-fn get_bin_dirs() -> anyhow::Result<BTreeSet<PathBuf>> {
+pub(super) fn get_bin_dirs() -> anyhow::Result<BTreeSet<PathBuf>> {
     let paths: BTreeSet<_> = std::env::var("PATH")?
         .split(":")
         .filter_map(|path_str| PathBuf::from(path_str).canonicalize().ok())
@@ -242,19 +391,23 @@ fn get_bin_dirs() -> anyhow::Result<BTreeSet<PathBuf>> {
     Ok(paths)
 }
 
-/// get the xdg data dirs as a vec
-fn get_data_dirs() -> anyhow::Result<Vec<PathBuf>> {
-    let mut data_dir: Vec<_> = std::env::var("XDG_DATA_DIRS")
+/// get the applications dirs of the xdg data dirs as listed, symlinks included
+pub(super) fn get_raw_data_dirs() -> anyhow::Result<Vec<PathBuf>> {
+    Ok(std::env::var("XDG_DATA_DIRS")
         .or_else(|err| match err {
             VarError::NotPresent => Ok("/usr/local/share:/usr/share".to_owned()),
             _ => Err(err),
         })?
         .split(":")
-        .filter_map(|path_str| {
-            PathBuf::from(format!("{path_str}/applications"))
-                .canonicalize()
-                .ok()
-        })
+        .map(|path_str| PathBuf::from(format!("{path_str}/applications")))
+        .collect())
+}
+
+/// get the xdg data dirs as a vec
+pub(super) fn get_data_dirs() -> anyhow::Result<Vec<PathBuf>> {
+    let mut data_dir: Vec<_> = get_raw_data_dirs()?
+        .into_iter()
+        .filter_map(|data_dir| data_dir.canonicalize().ok())
         .filter(|data_dir| data_dir.exists())
         .dedup()
         .collect();
@@ -265,92 +418,164 @@ fn get_data_dirs() -> anyhow::Result<Vec<PathBuf>> {
     Ok(data_dir)
 }
 
-/// get the app descriptors from app.desktop files in data dirs
+/// get the app descriptor from an app.desktop file
 /// verify that the executable valid
 /// use the app.desktop filename as the app id
-async fn get_apps() -> anyhow::Result<AppNameToAppMap> {
-    let paths = get_bin_dirs()?;
-    let data_dirs = get_data_dirs()?;
-
-    let mut apps = AppNameToAppMap::from(BTreeMap::new());
-
-    for data_dir in data_dirs.iter() {
-        let mut entries = fs::read_dir(data_dir).await?;
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            let app_id = path
-                .file_stem()
-                .and_then(|filename| filename.to_str())
-                .map(|s| s.to_owned())
-                .unwrap_or("na".to_owned());
-
-            if path.extension() == Some(OsStr::new("desktop")) {
-                let desktop = tokio::task::spawn_blocking(|| parse_entry(path)).await??;
-                let is_visible_app = desktop
-                    .get_desk_entry("Type")
-                    .is_some_and(|typo| typo == "Application")
-                    && desktop.get_desk_entry("Hidden").is_none_or(|s| s != "true")
-                    && desktop
-                        .get_desk_entry("NoDisplay")
-                        .is_none_or(|s| s != "true");
-
-                let name = desktop.get_desk_entry("Name");
-                let exec = desktop.get_desk_entry("Exec");
-
-                let try_exec = desktop.get_desk_entry("TryExec");
-                let comment = desktop.get_desk_entry("Comment");
-                let icon = desktop.get_desk_entry("Icon").cloned();
-                let icon = tokio::task::spawn_blocking(move || {
-                    icon.and_then(|name| fdo_icons::find(&name))
-                })
-                .await?;
-                let gen_name = desktop.get_desk_entry("GenericName");
-                let categores = desktop.get_desk_entry("Categories").map(|cats| {
-                    cats.split(";")
-                        .map(|str| str.to_owned())
-                        .collect::<Vec<String>>()
-                });
-
-                if is_visible_app
-                    && let Some(name) = name
-                    && let Some(exec) = exec
-                    && verify_exec(exec, try_exec, &paths).await
-                {
-                    let exec = exec
-                        .split_whitespace()
-                        .filter(|token| !token.starts_with("%"))
-                        .collect::<Vec<_>>()
-                        .join(" ");
-
-                    apps.insert(
-                        app_id.clone(),
-                        AppDesc::new(
-                            name.to_owned(),
-                            0,
-                            app_id,
-                            exec.to_owned(),
-                            gen_name.cloned(),
-                            comment.cloned(),
-                            icon,
-                            categores,
-                        ),
-                    );
-                }
-            }
+/// returns None for files that aren't visible apps
+pub(super) async fn parse_app(
+    path: &Path,
+    bin_dirs: &BTreeSet<PathBuf>,
+) -> anyhow::Result<Option<AppDesc>> {
+    let app_id = path
+        .file_stem()
+        .and_then(|filename| filename.to_str())
+        .map(|s| s.to_owned())
+        .unwrap_or("na".to_owned());
+
+    let desktop = {
+        let path = path.to_owned();
+        tokio::task::spawn_blocking(|| parse_entry(path)).await??
+    };
+    let is_visible_app = desktop
+        .get_desk_entry("Type")
+        .is_some_and(|typo| typo == "Application")
+        && desktop.get_desk_entry("Hidden").is_none_or(|s| s != "true")
+        && desktop
+            .get_desk_entry("NoDisplay")
+            .is_none_or(|s| s != "true")
+        && desktop
+            .get_desk_entry("OnlyShowIn")
+            .is_none_or(|desktops| is_current_desktop(desktops))
+        && desktop
+            .get_desk_entry("NotShowIn")
+            .is_none_or(|desktops| !is_current_desktop(desktops));
+
+    let name = desktop.get_localized_desk_entry("Name");
+    let exec = desktop.get_desk_entry("Exec");
+
+    let try_exec = desktop.get_desk_entry("TryExec");
+    let comment = desktop.get_localized_desk_entry("Comment");
+    let icon_name = desktop.get_desk_entry("Icon").cloned();
+    let icon_path = {
+        let icon_name = icon_name.clone();
+        tokio::task::spawn_blocking(move || icon_name.and_then(|name| fdo_icons::find_path(&name)))
+            .await?
+    };
+    let work_dir = desktop.get_desk_entry("Path").map(PathBuf::from);
+    let terminal = desktop
+        .get_desk_entry("Terminal")
+        .is_some_and(|s| s == "true");
+    let gen_name = desktop.get_localized_desk_entry("GenericName");
+    let keywords = desktop
+        .get_localized_desk_entry("Keywords")
+        .map(|keywords| {
+            keywords
+                .split(";")
+                .filter(|keyword| !keyword.is_empty())
+                .map(|str| str.to_owned())
+                .collect::<Vec<String>>()
+        });
+    let categores = desktop.get_desk_entry("Categories").map(|cats| {
+        cats.split(";")
+            .map(|str| str.to_owned())
+            .collect::<Vec<String>>()
+    });
+
+    let exec = name.zip(exec).and_then(|(name, exec)| {
+        let codes = FieldCodes {
+            icon: icon_name.as_deref(),
+            name,
+            desktop_file: path,
+        };
+        desktop_exec::parse(exec, &codes)
+            .inspect_err(|err| {
+                info!("Error parsing exec of {app_id}: {err:?}");
+            })
+            .ok()
+    });
+
+    if is_visible_app
+        && let Some(name) = name
+        && let Some(exec) = exec
+        && verify_exec(&exec, try_exec, bin_dirs).await
+    {
+        let codes = FieldCodes {
+            icon: icon_name.as_deref(),
+            name,
+            desktop_file: path,
+        };
+        let actions = get_actions(&desktop, &codes).await;
+
+        return Ok(Some(AppDesc::new(
+            name.to_owned(),
+            0,
+            app_id,
+            exec,
+            work_dir,
+            terminal,
+            gen_name.cloned(),
+            comment.cloned(),
+            icon_path.clone(),
+            icon_path.map(fdo_icons::from_path),
+            categores,
+            keywords,
+            actions,
+        )));
+    }
+
+    Ok(None)
+}
+
+/// get the actions listed in `Actions=`, skipping any without a name or valid exec
+async fn get_actions(desktop: &Entry, codes: &FieldCodes<'_>) -> Vec<AppAction> {
+    let Some(action_ids) = desktop.get_desk_entry("Actions") else {
+        return Vec::new();
+    };
+
+    let mut actions = Vec::new();
+    for action_id in action_ids.split(";").filter(|id| !id.is_empty()) {
+        let name = desktop.get_localized_action_entry(action_id, "Name");
+        let exec = desktop
+            .get_action_entry(action_id, "Exec")
+            .and_then(|exec| {
+                desktop_exec::parse(exec, codes)
+                    .inspect_err(|err| {
+                        info!("Error parsing exec of action {action_id}: {err:?}");
+                    })
+                    .ok()
+            });
+        let icon_name = desktop.get_action_entry(action_id, "Icon").cloned();
+        let icon_path = tokio::task::spawn_blocking(move || {
+            icon_name.and_then(|name| fdo_icons::find_path(&name))
+        })
+        .await
+        .ok()
+        .flatten();
+
+        if let Some(name) = name
+            && let Some(exec) = exec
+        {
+            actions.push(AppAction {
+                id: action_id.to_owned(),
+                name: name.to_owned(),
+                exec,
+                icon_path: icon_path.clone(),
+                icon: icon_path.map(fdo_icons::from_path),
+            });
         }
     }
 
-    Ok(apps)
+    actions
 }
 
 /// verify the executable from .desktop file is valid in $PATHS
-async fn verify_exec(exec: &String, try_exec: Option<&String>, paths: &BTreeSet<PathBuf>) -> bool {
-    let maybe_exec = try_exec
-        .unwrap_or(exec)
-        .split_whitespace()
-        // grab first part, should be exec name or full path
-        .next()
-        .map(PathBuf::from);
+async fn verify_exec(
+    exec: &[String],
+    try_exec: Option<&String>,
+    paths: &BTreeSet<PathBuf>,
+) -> bool {
+    // first part of argv should be exec name or full path
+    let maybe_exec = try_exec.or(exec.first()).map(PathBuf::from);
 
     if let Some(exec_path) = maybe_exec {
         if exec_path.is_absolute() {
@@ -363,74 +588,111 @@ async fn verify_exec(exec: &String, try_exec: Option<&String>, paths: &BTreeSet<
     }
 }
 
-trait EntryExt {
-    fn get_desk_entry(&self, attr: impl AsRef<str>) -> Option<&String>;
+/// check a `;` separated list of desktops against $XDG_CURRENT_DESKTOP
+fn is_current_desktop(desktops: &str) -> bool {
+    desktops.split(";").any(|desktop| {
+        CURRENT_DESKTOPS
+            .iter()
+            .any(|current| current.eq_ignore_ascii_case(desktop))
+    })
 }
 
-impl EntryExt for Entry {
-    fn get_desk_entry(&self, attr: impl AsRef<str>) -> Option<&String> {
-        self.get("Desktop Entry", attr)
-            .and_then(|entries| entries.first())
+/// get the locale suffixes from $LC_ALL, $LC_MESSAGES or $LANG
+/// lang_COUNTRY.ENCODING@MODIFIER gives lang_COUNTRY@MODIFIER, lang_COUNTRY, lang@MODIFIER, lang
+fn get_locale_keys() -> Vec<String> {
+    let Some(locale) = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+    else {
+        return Vec::new();
+    };
+
+    let (locale, modifier) = match locale.split_once("@") {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale.as_str(), None),
+    };
+    let locale = locale.split(".").next().unwrap_or_default();
+    let (lang, country) = match locale.split_once("_") {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+
+    if lang.is_empty() || lang == "C" || lang == "POSIX" {
+        return Vec::new();
     }
+
+    [
+        country
+            .zip(modifier)
+            .map(|(country, modifier)| format!("{lang}_{country}@{modifier}")),
+        country.map(|country| format!("{lang}_{country}")),
+        modifier.map(|modifier| format!("{lang}@{modifier}")),
+        Some(lang.to_owned()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
 }
 
-/// cache execution counts in local data dir
-#[derive(Debug, Constructor, Deref, DerefMut, Default, Deserialize, Serialize, Clone)]
-pub struct CountCache(pub HashMap<String, usize>);
+trait EntryExt {
+    fn get_desk_entry(&self, attr: impl AsRef<str>) -> Option<&String>;
+    fn get_action_entry(&self, action_id: &str, attr: impl AsRef<str>) -> Option<&String>;
+    fn get_localized(&self, section: &str, attr: &str) -> Option<&String>;
 
-impl CountCache {
-    fn get_path() -> PathBuf {
-        dirs::data_local_dir()
-            .unwrap_or(PathBuf::from("."))
-            .join("icedshell/launcher_counts.json")
+    fn get_localized_desk_entry(&self, attr: &str) -> Option<&String> {
+        self.get_localized("Desktop Entry", attr)
     }
 
-    async fn ensure_dir(path: &Path) -> anyhow::Result<()> {
-        if let Some(basename) = path.parent() {
-            fs::create_dir_all(basename).await?;
-        }
-
-        Ok(())
+    fn get_localized_action_entry(&self, action_id: &str, attr: &str) -> Option<&String> {
+        self.get_localized(&format!("Desktop Action {action_id}"), attr)
     }
+}
 
-    async fn load() -> anyhow::Result<Self> {
-        let path = Self::get_path();
-
-        Self::ensure_dir(&path).await?;
-
-        if !fs::try_exists(&path).await? {
-            return Ok(Self::default());
-        };
-
-        fs::read_to_string(&path)
-            .await
-            .map_err(anyhow::Error::from)
-            .and_then(|file_str| serde_json::from_str(&file_str).map_err(anyhow::Error::from))
+impl EntryExt for Entry {
+    fn get_desk_entry(&self, attr: impl AsRef<str>) -> Option<&String> {
+        self.get("Desktop Entry", attr)
+            .and_then(|entries| entries.first())
     }
 
-    fn jsonify(&self) -> anyhow::Result<String> {
-        Ok(serde_json::to_string(self)?)
+    fn get_action_entry(&self, action_id: &str, attr: impl AsRef<str>) -> Option<&String> {
+        self.get(format!("Desktop Action {action_id}"), attr)
+            .and_then(|entries| entries.first())
     }
 
-    async fn save(json_str: String) -> anyhow::Result<()> {
-        let path = Self::get_path();
-        Self::ensure_dir(&path).await?;
-
-        fs::write(&path, &json_str).await?;
-        Ok(())
+    /// get the value for the best matching locale, falling back to the unlocalized key
+    fn get_localized(&self, section: &str, attr: &str) -> Option<&String> {
+        LOCALE_KEYS
+            .iter()
+            .find_map(|locale| {
+                self.get_with_param(section, attr, locale)
+                    .and_then(|entries| entries.first())
+            })
+            .or_else(|| self.get(section, attr).and_then(|entries| entries.first()))
     }
+}
 
-    fn on_app_exec(&mut self, app_id: &str) {
-        *self.entry(app_id.to_owned()).or_default() += 1;
-    }
+fn action_res(app: &AppDesc, action: &AppAction) -> Res<AppEntryId> {
+    Res::new(
+        AppEntryId::Action(app.app_id.clone(), action.id.clone()),
+        action.icon.clone().or(app.icon.clone()),
+        format!("  ↳ {}", action.name),
+        None,
+    )
 }
 
-impl From<&AppDesc> for Res<String> {
+impl From<&AppDesc> for Res<AppEntryId> {
     fn from(value: &AppDesc) -> Self {
+        let content = if value.actions.is_empty() {
+            value.name.clone()
+        } else {
+            format!("{} ›", value.name)
+        };
+
         Res::new(
-            value.app_id.clone(),
+            AppEntryId::App(value.app_id.clone()),
             value.icon.clone(),
-            value.name.clone(),
+            content,
             value.comment.clone(),
         )
     }
@@ -0,0 +1,310 @@
+//! evaluate arithmetic expressions and unit conversions
+//! e.g. `2 * (3 + 4)`, `sqrt(2)^2`, `1.5 GiB to MB`, `100 f in c`, `90 min to h`
+use std::{iter::Peekable, str::Chars};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dim {
+    Bytes,
+    Temp,
+    Time,
+}
+
+struct Unit {
+    names: &'static [&'static str],
+    dim: Dim,
+    /// scale to the base unit of the dimension
+    factor: f64,
+    /// added after scaling, only used by temperatures
+    offset: f64,
+}
+
+impl Unit {
+    const fn new(names: &'static [&'static str], dim: Dim, factor: f64) -> Self {
+        Self {
+            names,
+            dim,
+            factor,
+            offset: 0.0,
+        }
+    }
+
+    fn to_base(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    fn to_unit(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+}
+
+/// bytes in bytes, temperature in kelvin, time in seconds
+const UNITS: &[Unit] = &[
+    Unit::new(&["b", "byte", "bytes"], Dim::Bytes, 1.0),
+    Unit::new(&["kb", "kilobyte", "kilobytes"], Dim::Bytes, 1e3),
+    Unit::new(&["mb", "megabyte", "megabytes"], Dim::Bytes, 1e6),
+    Unit::new(&["gb", "gigabyte", "gigabytes"], Dim::Bytes, 1e9),
+    Unit::new(&["tb", "terabyte", "terabytes"], Dim::Bytes, 1e12),
+    Unit::new(&["pb", "petabyte", "petabytes"], Dim::Bytes, 1e15),
+    Unit::new(&["kib", "kibibyte", "kibibytes"], Dim::Bytes, 1024.0),
+    Unit::new(&["mib", "mebibyte", "mebibytes"], Dim::Bytes, 1048576.0),
+    Unit::new(&["gib", "gibibyte", "gibibytes"], Dim::Bytes, 1073741824.0),
+    Unit::new(
+        &["tib", "tebibyte", "tebibytes"],
+        Dim::Bytes,
+        1099511627776.0,
+    ),
+    Unit::new(
+        &["pib", "pebibyte", "pebibytes"],
+        Dim::Bytes,
+        1125899906842624.0,
+    ),
+    Unit::new(&["k", "°k", "kelvin"], Dim::Temp, 1.0),
+    Unit {
+        names: &["c", "°c", "celsius"],
+        dim: Dim::Temp,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        names: &["f", "°f", "fahrenheit"],
+        dim: Dim::Temp,
+        factor: 5.0 / 9.0,
+        offset: 273.15 - 32.0 * 5.0 / 9.0,
+    },
+    Unit::new(&["ms", "millisecond", "milliseconds"], Dim::Time, 1e-3),
+    Unit::new(&["s", "sec", "secs", "second", "seconds"], Dim::Time, 1.0),
+    Unit::new(&["min", "mins", "minute", "minutes"], Dim::Time, 60.0),
+    Unit::new(&["h", "hr", "hrs", "hour", "hours"], Dim::Time, 3600.0),
+    Unit::new(&["d", "day", "days"], Dim::Time, 86400.0),
+    Unit::new(&["w", "wk", "week", "weeks"], Dim::Time, 604800.0),
+];
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let name = name.to_lowercase();
+    UNITS
+        .iter()
+        .find(|unit| unit.names.contains(&name.as_str()))
+}
+
+/// an evaluated query
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calculation {
+    /// the query as understood
+    pub expr: String,
+    /// the number alone
+    pub result: String,
+    /// the unit converted to
+    pub unit: Option<String>,
+}
+
+/// evaluate the query if it looks like arithmetic or a unit conversion
+pub fn eval(query: &str) -> Option<Calculation> {
+    let query = query.trim();
+    // plain numbers and words aren't calculations
+    if !query.contains(|c: char| c.is_ascii_digit()) || query.parse::<f64>().is_ok() {
+        return None;
+    }
+
+    convert(query).or_else(|| {
+        let value = Parser::new(query).parse()?;
+        Some(Calculation {
+            expr: query.to_owned(),
+            result: format_number(value),
+            unit: None,
+        })
+    })
+}
+
+/// `<expr> <unit> to|in|as <unit>`
+fn convert(query: &str) -> Option<Calculation> {
+    let lower = query.to_lowercase();
+    let (idx, sep) = [" to ", " in ", " as "]
+        .into_iter()
+        .filter_map(|sep| lower.rfind(sep).map(|idx| (idx, sep)))
+        .max()?;
+    let lhs = query.get(..idx)?;
+    let target_name = query.get(idx + sep.len()..)?.trim();
+    let target = find_unit(target_name)?;
+
+    let lhs = lhs.trim();
+    let unit_start = lhs
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphabetic() || *c == '°')
+        .last()
+        .map(|(idx, _)| idx)?;
+    let (expr, unit_name) = lhs.split_at(unit_start);
+    let unit = find_unit(unit_name)?;
+
+    if unit.dim != target.dim {
+        return None;
+    }
+
+    let value = Parser::new(expr).parse()?;
+    let converted = target.to_unit(unit.to_base(value));
+
+    Some(Calculation {
+        expr: format!("{} {unit_name} to {target_name}", expr.trim()),
+        result: format_number(converted),
+        unit: Some(target_name.to_owned()),
+    })
+}
+
+fn format_number(value: f64) -> String {
+    let abs = value.abs();
+    if abs != 0.0 && !(1e-6..1e15).contains(&abs) {
+        return format!("{value:e}");
+    }
+
+    let formatted = format!("{value:.10}");
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    if formatted == "-0" {
+        "0".to_owned()
+    } else {
+        formatted.to_owned()
+    }
+}
+
+/// recursive descent parser
+/// expr  = term (('+' | '-') term)*
+/// term  = unary (('*' | '/' | '%') unary)*
+/// unary = ('-' | '+') unary | power
+/// power = atom ('^' unary)?
+/// atom  = number | '(' expr ')' | const | func '(' expr ')'
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+        }
+    }
+
+    /// parse the whole input, None if anything is left over
+    fn parse(mut self) -> Option<f64> {
+        let value = self.expr()?;
+        self.skip_whitespace();
+
+        (self.chars.peek().is_none() && value.is_finite()).then_some(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if_eq(&expected).is_some()
+    }
+
+    fn expr(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                value /= self.unary()?;
+            } else if self.eat('%') {
+                value %= self.unary()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Option<f64> {
+        if self.eat('-') {
+            return self.unary().map(|value| -value);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Option<f64> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            return self.unary().map(|exp| base.powf(exp));
+        }
+        Some(base)
+    }
+
+    fn atom(&mut self) -> Option<f64> {
+        self.skip_whitespace();
+
+        if self.eat('(') {
+            let value = self.expr()?;
+            return self.eat(')').then_some(value);
+        }
+
+        match self.chars.peek() {
+            Some(c) if c.is_ascii_digit() || *c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() => self.ident(),
+            _ => None,
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let mut num = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || *c == '.' || *c == '_')
+        {
+            if c != '_' {
+                num.push(c);
+            }
+        }
+
+        num.parse().ok()
+    }
+
+    fn ident(&mut self) -> Option<f64> {
+        let mut ident = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric()) {
+            ident.push(c.to_ascii_lowercase());
+        }
+
+        match ident.as_str() {
+            "pi" => return Some(std::f64::consts::PI),
+            "e" => return Some(std::f64::consts::E),
+            _ => (),
+        }
+
+        let func: fn(f64) -> f64 = match ident.as_str() {
+            "sqrt" => f64::sqrt,
+            "abs" => f64::abs,
+            "ln" => f64::ln,
+            "log" => f64::log10,
+            "exp" => f64::exp,
+            "sin" => f64::sin,
+            "cos" => f64::cos,
+            "tan" => f64::tan,
+            "floor" => f64::floor,
+            "ceil" => f64::ceil,
+            "round" => f64::round,
+            _ => return None,
+        };
+
+        if !self.eat('(') {
+            return None;
+        }
+        let arg = self.expr()?;
+        self.eat(')').then(|| func(arg))
+    }
+}
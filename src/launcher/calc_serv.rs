@@ -0,0 +1,60 @@
+use iced::Task;
+
+use crate::{
+    clipboard::data_control::{self, Clip},
+    launcher::{
+        calc,
+        modi::{Modi, Query, Res},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {}
+
+/// evaluates the query as a calculation, the result is copied on exec without
+/// the unit
+#[derive(Default)]
+pub struct CalcServ {
+    res: Vec<Res<String>>,
+}
+
+impl Modi for CalcServ {
+    /// the result, without the unit
+    type Id = String;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.res.len()
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, Query { term, page, .. }: Query) -> Task<Self::Message> {
+        self.res = term
+            .filter(|_| page == 0)
+            .as_deref()
+            .and_then(calc::eval)
+            .map(|calc::Calculation { expr, result, unit }| {
+                let content = match unit {
+                    Some(unit) => format!("{result} {unit}"),
+                    None => result.clone(),
+                };
+                Res::new(result, None, content, Some(expr))
+            })
+            .into_iter()
+            .collect();
+
+        Task::none()
+    }
+
+    fn exec(&mut self, result: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        data_control::copy(Clip::text(result));
+        Ok(Task::none())
+    }
+}
@@ -0,0 +1,141 @@
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+
+use crate::{
+    clipboard::{
+        data_control,
+        history_serv::{ClipContent, ClipEntry, ClipHistory},
+    },
+    feature::Service,
+    launcher::modi::{self, Modi, Query, Res},
+    widget::fdo_icons,
+};
+
+const MAX_CONTENT_CHARS: usize = 80;
+const MAX_TOOLTIP_CHARS: usize = 400;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadHistory(ClipHistory),
+}
+
+/// the clipboard history kept by the daemon
+pub struct ClipServ {
+    history: ClipHistory,
+    last_query: Query,
+    /// by hash of the entry
+    res: Vec<Res<u64>>,
+    /// num of results across all pages
+    total: usize,
+}
+
+impl Service for ClipServ {
+    type Message = Message;
+    /// the history from the daemon
+    type Init = ClipHistory;
+
+    fn new<O: MaybeSend + 'static>(
+        history: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let mut serv = Self {
+            history,
+            last_query: Query::new(None, 0, 10),
+            res: Vec::new(),
+            total: 0,
+        };
+        let task = serv.query(serv.last_query.clone());
+
+        (serv, task.map(f))
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::LoadHistory(history) => {
+                self.history = history;
+                self.query(self.last_query.clone())
+            }
+        }
+    }
+}
+
+impl Modi for ClipServ {
+    type Id = u64;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+        let Query { term, page, limit } = query;
+
+        let mut entries: Vec<_> = self.history.iter().collect();
+        if let Some(term) = term {
+            entries = modi::match_by(&term, entries, |entry| match &entry.content {
+                ClipContent::Text(text) => [(Some(text.as_str()), 1)],
+                ClipContent::Image { mime, .. } => [(Some(mime.as_str()), 1)],
+            });
+        }
+
+        self.total = entries.len();
+
+        self.res = entries
+            .into_iter()
+            .skip(page * limit)
+            .take(limit)
+            .map(Res::from)
+            .collect();
+
+        Task::none()
+    }
+
+    fn exec(&mut self, hash: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        let Some(entry) = self.history.iter().find(|entry| entry.hash == *hash) else {
+            anyhow::bail!("Clip not found in history");
+        };
+
+        data_control::copy(entry.clip()?);
+        Ok(Task::none())
+    }
+}
+
+impl From<&ClipEntry> for Res<u64> {
+    fn from(entry: &ClipEntry) -> Self {
+        match &entry.content {
+            ClipContent::Text(text) => {
+                let first_line = text.trim().lines().next().unwrap_or_default();
+                let content = truncate(first_line, MAX_CONTENT_CHARS);
+                let tooltip =
+                    (content != text.as_str()).then(|| truncate(text.trim(), MAX_TOOLTIP_CHARS));
+
+                Res::new(entry.hash, None, content, tooltip)
+            }
+            ClipContent::Image { mime, thumb, .. } => Res::new(
+                entry.hash,
+                Some(fdo_icons::from_path(thumb.clone())),
+                mime.clone(),
+                None,
+            ),
+        }
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_owned(),
+    }
+}
@@ -0,0 +1,153 @@
+//! parse the Exec key of desktop entries
+//! https://specifications.freedesktop.org/desktop-entry-spec/latest/exec-variables.html
+use std::path::Path;
+
+use anyhow::bail;
+
+/// values the field codes of an Exec key expand to
+pub struct FieldCodes<'a> {
+    /// %i
+    pub icon: Option<&'a str>,
+    /// %c
+    pub name: &'a str,
+    /// %k
+    pub desktop_file: &'a Path,
+}
+
+/// split an Exec key into argv, expanding field codes
+/// no files or urls are passed, so %f %F %u %U expand to nothing
+pub fn parse(exec: &str, codes: &FieldCodes) -> anyhow::Result<Vec<String>> {
+    let args = split(&unescape(exec))?;
+
+    Ok(args
+        .into_iter()
+        .flat_map(|arg| match arg.as_str() {
+            "%i" => codes
+                .icon
+                .map(|icon| vec!["--icon".to_owned(), icon.to_owned()])
+                .unwrap_or_default(),
+            "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => Vec::new(),
+            _ => vec![expand(&arg, codes)],
+        })
+        .collect())
+}
+
+/// join argv back into a line for display, quoting args with spaces
+pub fn join(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// replace the escape sequences of string values
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => out.push(' '),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('\\') => out.push('\\'),
+            // left for the quoting rules
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+/// split on whitespace, honoring double quoted args and their escapes
+fn split(exec: &str) -> anyhow::Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' => quoted = false,
+                '\\' => match chars.next() {
+                    Some(escaped @ ('"' | '`' | '$' | '\\')) => arg.push(escaped),
+                    Some(other) => {
+                        arg.push('\\');
+                        arg.push(other);
+                    }
+                    None => bail!("Trailing escape in Exec: {exec}"),
+                },
+                _ => arg.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                quoted = true;
+                in_arg = true;
+            }
+            ' ' | '\t' | '\n' => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            _ => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quoted {
+        bail!("Unterminated quote in Exec: {exec}");
+    }
+    if in_arg {
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+/// expand field codes embedded in an arg
+fn expand(arg: &str, codes: &FieldCodes) -> String {
+    let mut out = String::with_capacity(arg.len());
+    let mut chars = arg.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('%') => out.push('%'),
+            Some('c') => out.push_str(codes.name),
+            Some('k') => out.push_str(&codes.desktop_file.to_string_lossy()),
+            Some('i') => out.push_str(codes.icon.unwrap_or_default()),
+            // deprecated or file codes
+            Some(_) | None => (),
+        }
+    }
+
+    out
+}
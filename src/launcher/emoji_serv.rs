@@ -0,0 +1,185 @@
+use std::{cmp, sync::LazyLock};
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use itertools::Itertools;
+use tracing::info;
+
+use crate::{
+    clipboard::data_control::{self, Clip},
+    config::CONFIG,
+    feature::Service,
+    launcher::{
+        frecency::{self, FrecencyMap},
+        modi::{self, Modi, Query, Res},
+        symbols::SYMBOLS,
+    },
+};
+
+const COUNTS_FILE: &str = "launcher_emoji_counts.json";
+/// wtype waits so the launcher can close and hand back keyboard focus
+const TYPE_DELAY_MS: &str = "200";
+
+/// an emoji or symbol with the text it's matched on
+struct Glyph {
+    glyph: &'static str,
+    name: &'static str,
+    keywords: String,
+}
+
+static GLYPHS: LazyLock<Vec<Glyph>> = LazyLock::new(|| {
+    let emojis = emojis::iter().map(|emoji| Glyph {
+        glyph: emoji.as_str(),
+        name: emoji.name(),
+        keywords: emoji
+            .shortcodes()
+            .map(|shortcode| shortcode.replace('_', " "))
+            .join(" "),
+    });
+    let symbols = SYMBOLS.iter().map(|(glyph, name, keywords)| Glyph {
+        glyph,
+        name,
+        keywords: keywords.to_string(),
+    });
+
+    emojis.chain(symbols).collect()
+});
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadCache(FrecencyMap),
+}
+
+/// bundled emoji and symbols, recently used first
+pub struct EmojiServ {
+    counts: FrecencyMap,
+    last_query: Query,
+    /// by glyph
+    res: Vec<Res<String>>,
+    /// num of results across all pages
+    total: usize,
+}
+
+impl Service for EmojiServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let init_cache = Task::future(async {
+            FrecencyMap::load(FrecencyMap::get_path(COUNTS_FILE))
+                .await
+                .inspect_err(|err| {
+                    info!("Error loading emoji counts: {err:?}");
+                })
+                .unwrap_or_default()
+        })
+        .map(Message::LoadCache);
+
+        (
+            Self {
+                counts: FrecencyMap::default(),
+                last_query: Query::new(None, 0, 10),
+                res: Vec::new(),
+                total: 0,
+            },
+            init_cache.map(f),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::LoadCache(counts) => {
+                self.counts = counts;
+                self.query(self.last_query.clone())
+            }
+        }
+    }
+}
+
+impl Modi for EmojiServ {
+    type Id = String;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+        let Query { term, page, limit } = query;
+
+        let mut glyphs: Vec<_> = GLYPHS
+            .iter()
+            .map(|glyph| (glyph, self.counts.score(glyph.glyph)))
+            .collect();
+
+        match term {
+            Some(term) => {
+                glyphs = modi::score_by(&term, glyphs, |(glyph, _)| {
+                    [(Some(glyph.name), 100), (Some(&glyph.keywords), 80)]
+                })
+                .into_iter()
+                .map(|((glyph, frecency), score)| {
+                    (glyph, frecency, score + frecency::match_bonus(frecency))
+                })
+                .sorted_by_key(|(_, _, score)| cmp::Reverse(*score))
+                .map(|(glyph, frecency, _)| (glyph, frecency))
+                .collect();
+            }
+            // stable, so unused glyphs keep the table order
+            None => glyphs.sort_by_key(|(_, frecency)| cmp::Reverse(*frecency)),
+        }
+
+        self.total = glyphs.len();
+
+        self.res = glyphs
+            .into_iter()
+            .skip(page * limit)
+            .take(limit)
+            .map(|(glyph, _)| {
+                Res::new(
+                    glyph.glyph.to_owned(),
+                    None,
+                    format!("{}  {}", glyph.glyph, glyph.name),
+                    (!glyph.keywords.is_empty()).then(|| glyph.keywords.clone()),
+                )
+            })
+            .collect();
+
+        Task::none()
+    }
+
+    fn exec(&mut self, glyph: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        let out_task = if CONFIG.launcher.emoji_type {
+            modi::spawn(["wtype", "-s", TYPE_DELAY_MS, "--", glyph], None, false)?;
+            Task::none()
+        } else {
+            data_control::copy(Clip::text(glyph));
+            Task::none()
+        };
+
+        self.counts.on_exec(glyph);
+        let save_task = self
+            .counts
+            .jsonify()
+            .map(|json_str| FrecencyMap::save(FrecencyMap::get_path(COUNTS_FILE), json_str))
+            .map(Task::future)
+            .map(Task::discard)?;
+
+        Ok(Task::batch([out_task, save_task]))
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use tracing::info;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::{
+    config::CONFIG,
+    feature::Service,
+    widget::fdo_icons::{self, FdIcon},
+};
+
+/// deep trees are rarely what we're looking for and are slow to walk
+const MAX_DEPTH: usize = 8;
+const MAX_FILES: usize = 100_000;
+/// dirs that are never worth searching
+const SKIP_DIRS: [&str; 4] = ["node_modules", "target", "__pycache__", "vendor"];
+/// a scan older than this is walked again when a launcher asks for it
+const RESCAN_AFTER: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+pub struct FileDesc {
+    pub path: PathBuf,
+    /// the path with the home dir replaced by `~`
+    pub display: String,
+    /// freedesktop icon name of the mime type
+    pub icon_name: String,
+}
+
+/// the walked files with the icons of their mime types
+#[derive(Debug, Default)]
+pub struct FileScan {
+    pub files: Vec<FileDesc>,
+    /// mime icon name to icon
+    pub icons: HashMap<String, Option<FdIcon>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// walk the roots again unless the last scan is recent
+    Rescan,
+    Scanned(Arc<FileScan>),
+}
+
+impl Message {
+    /// whether the files may have changed after this message
+    pub fn updates_files(&self) -> bool {
+        matches!(self, Message::Scanned(_))
+    }
+}
+
+/// files and dirs under the configured roots shared by every launcher
+/// the walk starts when a launcher first searches files, not on every open
+pub struct FileIndexServ {
+    scan: Option<Arc<FileScan>>,
+    scanned_at: Option<Instant>,
+    scanning: bool,
+}
+
+impl FileIndexServ {
+    pub fn scan(&self) -> Option<&Arc<FileScan>> {
+        self.scan.as_ref()
+    }
+}
+
+impl Service for FileIndexServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        Self {
+            scan: None,
+            scanned_at: None,
+            scanning: false,
+        }
+        .to_tuple()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::Rescan => {
+                let is_fresh = self
+                    .scanned_at
+                    .is_some_and(|scanned_at| scanned_at.elapsed() < RESCAN_AFTER);
+                if self.scanning || is_fresh {
+                    return Task::none();
+                }
+                self.scanning = true;
+
+                Task::future(async {
+                    tokio::task::spawn_blocking(scan)
+                        .await
+                        .inspect_err(|err| {
+                            info!("Error scanning files: {err:?}");
+                        })
+                        .unwrap_or_default()
+                })
+                .map(|scan| Message::Scanned(Arc::new(scan)))
+            }
+            Message::Scanned(scan) => {
+                self.scan = Some(scan);
+                self.scanned_at = Some(Instant::now());
+                self.scanning = false;
+                Task::none()
+            }
+        }
+    }
+}
+
+/// the configured roots, or the home dir
+fn roots() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or(PathBuf::from("."));
+
+    match &CONFIG.launcher.file_roots {
+        Some(roots) => roots
+            .iter()
+            .map(|root| match root.strip_prefix("~") {
+                Ok(rel) => home.join(rel),
+                Err(_) => root.clone(),
+            })
+            .collect(),
+        None => vec![home],
+    }
+}
+
+fn is_skipped(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.depth() > 0
+        && (name.starts_with('.')
+            || (entry.file_type().is_dir() && SKIP_DIRS.contains(&name.as_ref())))
+}
+
+/// freedesktop icon name for the mime type of the entry, e.g. `text-x-rust`
+fn icon_name(entry: &DirEntry) -> String {
+    if entry.file_type().is_dir() {
+        return "folder".to_owned();
+    }
+
+    match mime_guess::from_path(entry.path()).first() {
+        Some(mime) => format!("{}-{}", mime.type_(), mime.subtype()),
+        None => "text-x-generic".to_owned(),
+    }
+}
+
+/// fall back to the generic icon of the top level mime type, e.g. `image-x-generic`
+fn find_icon(icon_name: &str) -> Option<FdIcon> {
+    fdo_icons::find(icon_name).or_else(|| {
+        let (top_level, _) = icon_name.split_once('-')?;
+        fdo_icons::find(&format!("{top_level}-x-generic"))
+    })
+}
+
+/// walk the roots, skipping hidden and build dirs, then look up the mime icons
+fn scan() -> FileScan {
+    let home = dirs::home_dir().unwrap_or_default();
+
+    let files: Vec<_> = roots()
+        .into_iter()
+        .flat_map(|root| {
+            WalkDir::new(root)
+                .min_depth(1)
+                .max_depth(MAX_DEPTH)
+                .into_iter()
+                .filter_entry(|entry| !is_skipped(entry))
+                .filter_map(Result::ok)
+        })
+        .take(MAX_FILES)
+        .map(|entry| {
+            let display = match entry.path().strip_prefix(&home) {
+                Ok(rel) => format!("~/{}", rel.display()),
+                Err(_) => entry.path().display().to_string(),
+            };
+
+            FileDesc {
+                icon_name: icon_name(&entry),
+                display,
+                path: entry.into_path(),
+            }
+        })
+        .collect();
+
+    let mut icons = HashMap::new();
+    for file in &files {
+        if !icons.contains_key(&file.icon_name) {
+            icons.insert(file.icon_name.clone(), find_icon(&file.icon_name));
+        }
+    }
+
+    FileScan { files, icons }
+}
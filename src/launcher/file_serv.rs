@@ -0,0 +1,181 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use tracing::info;
+
+use crate::{
+    feature::Service,
+    launcher::{
+        file_index::FileScan,
+        modi::{self, Modi, Query, Res},
+    },
+};
+
+/// wait for a pause in typing before matching
+const MATCH_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// handled by the daemon, which scans unless the last scan is recent
+    ScanRequested,
+    LoadScan(Arc<FileScan>),
+    /// the query, its page of results and the total
+    Matched(Query, Vec<Res<PathBuf>>, usize),
+}
+
+/// files and dirs scanned by the daemon
+pub struct FileServ {
+    /// None until the daemon's first scan is done
+    scan: Option<Arc<FileScan>>,
+    /// asked the daemon for a fresh scan since the launcher opened
+    requested: bool,
+    /// bumped on every query, so a superseded match is skipped
+    generation: Arc<AtomicUsize>,
+    last_query: Query,
+    res: Vec<Res<PathBuf>>,
+    /// num of results across all pages
+    total: usize,
+}
+
+impl FileServ {
+    /// fuzzy match on a blocking thread, after a pause in typing
+    fn match_files(&self, scan: Arc<FileScan>, query: Query) -> Task<Message> {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let latest = self.generation.clone();
+
+        Task::future(async move {
+            if query.term.is_some() {
+                tokio::time::sleep(MATCH_DELAY).await;
+            }
+            if latest.load(Ordering::SeqCst) != generation {
+                return None;
+            }
+
+            tokio::task::spawn_blocking(move || {
+                let (res, total) = match_page(&scan, &query);
+                Message::Matched(query, res, total)
+            })
+            .await
+            .inspect_err(|err| {
+                info!("Error matching files: {err:?}");
+            })
+            .ok()
+        })
+        .and_then(Task::done)
+    }
+}
+
+impl Service for FileServ {
+    type Message = Message;
+    type Init = Option<Arc<FileScan>>;
+
+    fn new<O: MaybeSend + 'static>(
+        scan: Self::Init,
+        _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        (
+            Self {
+                scan,
+                requested: false,
+                generation: Arc::new(AtomicUsize::new(0)),
+                last_query: Query::new(None, 0, 10),
+                res: Vec::new(),
+                total: 0,
+            },
+            Task::none(),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::ScanRequested => Task::none(),
+            Message::LoadScan(scan) => {
+                self.scan = Some(scan);
+                self.query(self.last_query.clone())
+            }
+            Message::Matched(query, res, total) => {
+                if query == self.last_query {
+                    self.res = res;
+                    self.total = total;
+                }
+                Task::none()
+            }
+        }
+    }
+}
+
+impl Modi for FileServ {
+    type Id = PathBuf;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+
+        // a cached scan is matched while the daemon walks again
+        let request = if self.requested {
+            Task::none()
+        } else {
+            self.requested = true;
+            Task::done(Message::ScanRequested)
+        };
+
+        match self.scan.clone() {
+            Some(scan) => Task::batch([request, self.match_files(scan, query)]),
+            None => request,
+        }
+    }
+
+    fn exec(&mut self, path: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        modi::spawn([Path::new("xdg-open"), path.as_path()], None, false)?;
+        Ok(Task::none())
+    }
+}
+
+/// the page of results for the query and the num of matches
+fn match_page(scan: &FileScan, Query { term, page, limit }: &Query) -> (Vec<Res<PathBuf>>, usize) {
+    let mut files: Vec<_> = scan.files.iter().collect();
+    if let Some(term) = term {
+        files = modi::match_by(term, files, |file| [(Some(&file.display), 1)]);
+    }
+
+    let res = files
+        .iter()
+        .skip(page * limit)
+        .take(*limit)
+        .map(|file| {
+            let name = file
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.display.clone());
+            let icon = scan.icons.get(&file.icon_name).cloned().flatten();
+
+            Res::new(file.path.clone(), icon, name, Some(file.display.clone()))
+        })
+        .collect();
+
+    (res, files.len())
+}
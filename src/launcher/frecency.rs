@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+/// a use counts half as much a week later
+const HALF_LIFE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// score of a single use right now
+const USE_SCORE: f64 = 100.0;
+
+/// how often and how recently something was used
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(from = "FrecencyRepr")]
+pub struct Frecency {
+    pub count: usize,
+    /// unix timestamp in seconds
+    pub last_used: u64,
+    /// every use decayed by its age, as of `last_used`
+    decayed: f64,
+}
+
+impl Frecency {
+    fn bump(&mut self) {
+        let now = now().as_secs();
+        self.decayed = self.decayed_at(now) + USE_SCORE;
+        self.count += 1;
+        self.last_used = now;
+    }
+
+    fn decayed_at(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_used) as f64;
+        self.decayed * 0.5_f64.powf(age / HALF_LIFE.as_secs_f64())
+    }
+
+    /// the sum of every use, each weighted by how long ago it was
+    /// so a use today beats a hundred uses last year
+    pub fn score(&self) -> usize {
+        self.decayed_at(now().as_secs()).round() as usize
+    }
+}
+
+/// plain launch counts are read as never used recently
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FrecencyRepr {
+    Frecency {
+        count: usize,
+        last_used: u64,
+        decayed: f64,
+    },
+    Count(usize),
+}
+
+impl From<FrecencyRepr> for Frecency {
+    fn from(repr: FrecencyRepr) -> Self {
+        match repr {
+            FrecencyRepr::Frecency {
+                count,
+                last_used,
+                decayed,
+            } => Self {
+                count,
+                last_used,
+                decayed,
+            },
+            FrecencyRepr::Count(count) => Self {
+                count,
+                last_used: 0,
+                decayed: count as f64 * USE_SCORE,
+            },
+        }
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// frecency of keys, persisted as json in the local data dir
+#[derive(Debug, Deref, DerefMut, Default, Deserialize, Serialize, Clone)]
+pub struct FrecencyMap(HashMap<String, Frecency>);
+
+/// boost matches by how often and recently they were used
+/// capped so a frequently used entry can't beat a much better match
+pub fn match_bonus(frecency: usize) -> u32 {
+    (frecency as u32).saturating_mul(10).min(10_000)
+}
+
+impl FrecencyMap {
+    pub fn get_path(file_name: &str) -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or(PathBuf::from("."))
+            .join("icedshell")
+            .join(file_name)
+    }
+
+    async fn ensure_dir(path: &Path) -> anyhow::Result<()> {
+        if let Some(basename) = path.parent() {
+            fs::create_dir_all(basename).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn load(path: PathBuf) -> anyhow::Result<Self> {
+        Self::ensure_dir(&path).await?;
+
+        if !fs::try_exists(&path).await? {
+            return Ok(Self::default());
+        };
+
+        fs::read_to_string(&path)
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|file_str| serde_json::from_str(&file_str).map_err(anyhow::Error::from))
+    }
+
+    pub fn jsonify(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub async fn save(path: PathBuf, json_str: String) -> anyhow::Result<()> {
+        Self::ensure_dir(&path).await?;
+
+        fs::write(&path, &json_str).await?;
+        Ok(())
+    }
+
+    pub fn on_exec(&mut self, key: &str) {
+        self.entry(key.to_owned()).or_default().bump();
+    }
+
+    pub fn score(&self, key: &str) -> usize {
+        self.get(key).map(Frecency::score).unwrap_or_default()
+    }
+}
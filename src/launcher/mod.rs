@@ -1,29 +1,56 @@
+pub mod app_index;
 mod app_serv;
+mod calc;
+mod calc_serv;
+mod clip_serv;
+mod desktop_exec;
+mod emoji_serv;
+pub mod file_index;
+pub mod file_serv;
+mod frecency;
 mod modi;
+mod run_serv;
+mod ssh_serv;
+mod symbols;
+mod win_serv;
 
+use std::sync::Arc;
+
+use clap::{Args, ValueEnum};
 use derive_more::Display;
 use iced::{
-    Border, Element, Event, Length, Task,
+    Border, Element, Event, Length, Subscription, Task,
     advanced::graphics::futures::MaybeSend,
     alignment::Vertical,
     border, event,
     keyboard::{self, Key, key::Named},
+    mouse::ScrollDelta,
     padding,
     widget::{
-        Column, Space, column, container, operation::focus, row, text, text_input,
+        Column, Space, column, container, mouse_area, operation::focus, row, text, text_input,
         tooltip::Position,
     },
 };
 use iced_layershell::reexport::{
     Anchor, KeyboardInteractivity, Layer, NewLayerShellSettings, OutputOption,
 };
+use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use crate::{
+    clipboard::history_serv::ClipHistory,
     feature::{Comp, Feature, Service},
     launcher::{
-        app_serv::AppServ,
+        app_serv::{AppAction, AppDesc, AppNameToAppMap, AppServ},
+        calc_serv::CalcServ,
+        clip_serv::ClipServ,
+        emoji_serv::EmojiServ,
+        file_index::FileScan,
+        file_serv::FileServ,
         modi::{Modi, Query, Res},
+        run_serv::RunServ,
+        ssh_serv::SshServ,
+        win_serv::WinServ,
     },
     theme::CAT_THEME,
     types::MonitorId,
@@ -31,9 +58,67 @@ use crate::{
 };
 
 const NUM_OF_ITEMS: usize = 10;
-#[derive(Clone, Debug, Display)]
-enum PromptType {
+/// scrolled lines to turn a page
+const LINES_PER_PAGE: f32 = 1.0;
+/// pixels of a touchpad scroll counted as a line
+const PIXELS_PER_LINE: f32 = 50.0;
+#[derive(
+    Clone, Copy, Debug, Display, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize,
+)]
+pub enum ModiType {
+    #[default]
+    Apps,
     Run,
+    Windows,
+    Ssh,
+    Calc,
+    Files,
+    Clipboard,
+    Emoji,
+}
+
+impl ModiType {
+    const ALL: [Self; 8] = [
+        Self::Apps,
+        Self::Run,
+        Self::Windows,
+        Self::Ssh,
+        Self::Calc,
+        Self::Files,
+        Self::Clipboard,
+        Self::Emoji,
+    ];
+
+    /// typing the prefix at the start of the prompt switches to this modi
+    fn prefix(&self) -> Option<char> {
+        match self {
+            Self::Apps => None,
+            Self::Run => Some('>'),
+            Self::Windows => Some('#'),
+            Self::Ssh => Some('@'),
+            Self::Calc => Some('='),
+            Self::Files => Some('/'),
+            Self::Clipboard => Some('"'),
+            Self::Emoji => Some(':'),
+        }
+    }
+
+    fn from_prefix(prefix: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|modi_type| modi_type.prefix() == Some(prefix))
+    }
+
+    fn cycle(&self, forward: bool) -> Self {
+        let len = Self::ALL.len();
+        let idx = Self::ALL
+            .iter()
+            .position(|modi_type| modi_type == self)
+            .unwrap_or_default();
+        let next = if forward { idx + 1 } else { idx + len - 1 };
+
+        Self::ALL[next % len]
+    }
 }
 
 #[derive(Clone, Debug, Display)]
@@ -49,9 +134,28 @@ pub enum Message {
         bool,
     ),
     SearchUpdated(String),
-    OnSubmit(String),
+    OnSubmit,
     ExecSuccess,
+    NextModi,
+    PrevModi,
+    /// the cursor entered the nth result
+    ResHovered(usize),
+    ResClicked(usize),
+    ResScrolled(ScrollDelta),
+    /// the app index changed
+    AppsChanged(AppNameToAppMap),
     AppServ(app_serv::Message),
+    RunServ(run_serv::Message),
+    WinServ(win_serv::Message),
+    SshServ(ssh_serv::Message),
+    CalcServ(calc_serv::Message),
+    /// the daemon scanned the files again
+    FilesChanged(Arc<FileScan>),
+    FileServ(file_serv::Message),
+    /// the clipboard history changed
+    ClipsChanged(ClipHistory),
+    ClipServ(clip_serv::Message),
+    EmojiServ(emoji_serv::Message),
     LeftPressed(
         /// captured
         bool,
@@ -94,13 +198,34 @@ pub enum Message {
 
 pub struct Init {
     pub output: Option<MonitorId>,
+    pub args: LauncherArgs,
+    pub apps: AppNameToAppMap,
+    pub clips: ClipHistory,
+    pub files: Option<Arc<FileScan>>,
+}
+
+#[derive(Debug, Args, Clone, Display, Default, Deserialize, Serialize)]
+#[display("modi: {modi}")]
+pub struct LauncherArgs {
+    /// the modi to open the launcher in
+    #[arg(short, long, value_enum, default_value_t)]
+    pub modi: ModiType,
 }
 
 pub struct Launcher {
     search: String,
-    prompt_type: PromptType,
+    modi_type: ModiType,
     app_serv: AppServ,
+    run_serv: RunServ,
+    win_serv: WinServ,
+    ssh_serv: SshServ,
+    calc_serv: CalcServ,
+    file_serv: FileServ,
+    clip_serv: ClipServ,
+    emoji_serv: EmojiServ,
     page: usize,
+    /// lines scrolled since the last page turn
+    scrolled: f32,
     mode: Mode,
     selected: usize,
     monitor: Option<MonitorId>,
@@ -114,35 +239,61 @@ impl Comp for Launcher {
         input: Self::Init,
         f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
     ) -> (Self, Task<O>) {
-        let (app_serv, app_serv_task) = AppServ::new((), Message::AppServ);
-        (
-            Self {
-                app_serv,
-                page: 0,
-                prompt_type: PromptType::Run,
-                search: "".to_string(),
-                mode: Mode::Insert,
-                selected: 0,
-                monitor: input.output,
-            },
-            {
-                let outer_task = Task::future(async {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
-                })
-                .discard()
-                .chain(focus::<Message>("search-input"));
+        let (win_serv, win_serv_task) = WinServ::new(input.apps.clone(), Message::WinServ);
+        let (app_serv, app_serv_task) = AppServ::new(input.apps, Message::AppServ);
+        let (run_serv, run_serv_task) = RunServ::new((), Message::RunServ);
+        let (ssh_serv, ssh_serv_task) = SshServ::new((), Message::SshServ);
+        let (file_serv, file_serv_task) = FileServ::new(input.files, Message::FileServ);
+        let (clip_serv, clip_serv_task) = ClipServ::new(input.clips, Message::ClipServ);
+        let (emoji_serv, emoji_serv_task) = EmojiServ::new((), Message::EmojiServ);
+        let mut launcher = Self {
+            app_serv,
+            run_serv,
+            win_serv,
+            ssh_serv,
+            calc_serv: CalcServ::default(),
+            file_serv,
+            clip_serv,
+            emoji_serv,
+            page: 0,
+            scrolled: 0.0,
+            modi_type: input.args.modi,
+            search: "".to_string(),
+            mode: Mode::Insert,
+            selected: 0,
+            monitor: input.output,
+        };
+        let query_task = launcher.query();
 
-                Task::batch([app_serv_task, outer_task]).map(f)
-            },
-        )
+        (launcher, {
+            let outer_task = Task::future(async {
+                tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+            })
+            .discard()
+            .chain(focus::<Message>("search-input"));
+
+            Task::batch([
+                app_serv_task,
+                run_serv_task,
+                win_serv_task,
+                ssh_serv_task,
+                file_serv_task,
+                clip_serv_task,
+                emoji_serv_task,
+                query_task,
+                outer_task,
+            ])
+            .map(f)
+        })
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
-        event::listen_with(|event, status, _| {
+        let keys = event::listen_with(|event, status, _| {
             let captured = matches!(status, event::Status::Captured);
             match event {
                 Event::Keyboard(keyboard::Event::KeyPressed {
                     key: Key::Named(named),
+                    modifiers,
                     ..
                 }) => match named {
                     Named::Escape => Some(Message::EscapePressed(captured)),
@@ -150,6 +301,8 @@ impl Comp for Launcher {
                     Named::ArrowRight => Some(Message::RightPressed(captured)),
                     Named::ArrowDown => Some(Message::DownPressed(captured)),
                     Named::ArrowUp => Some(Message::UpPressed(captured)),
+                    Named::Tab if modifiers.shift() => Some(Message::PrevModi),
+                    Named::Tab => Some(Message::NextModi),
                     _ => None,
                 },
                 Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => match key.as_ref() {
@@ -162,7 +315,9 @@ impl Comp for Launcher {
                 },
                 _ => None,
             }
-        })
+        });
+
+        Subscription::batch([keys, self.win_serv.subscription().map(Message::WinServ)])
     }
 
     fn update(&mut self, message: Self::Message) -> iced::Task<Self::Message> {
@@ -176,33 +331,77 @@ impl Comp for Launcher {
                 Task::none()
             }
             Message::SearchUpdated(search) => {
-                self.search = search;
+                // a prefix only switches from apps or an empty search, other
+                // modi take it as input, e.g. a file path starting with `/`
+                let can_switch = self.modi_type == ModiType::Apps || self.search.is_empty();
+                let mut chars = search.chars();
+                match chars
+                    .next()
+                    .filter(|_| can_switch)
+                    .and_then(ModiType::from_prefix)
+                    .filter(|modi_type| *modi_type != self.modi_type)
+                {
+                    Some(modi_type) => {
+                        self.modi_type = modi_type;
+                        self.search = chars.collect();
+                        self.selected = 0;
+                    }
+                    None => self.search = search,
+                }
                 self.page = 0;
-                self.app_serv
-                    .query(Query::new(
-                        if self.search.is_empty() {
-                            None
-                        } else {
-                            Some(self.search.clone())
-                        },
-                        self.page,
-                        NUM_OF_ITEMS,
-                    ))
-                    .map(Message::AppServ)
+                self.query()
             }
-            Message::OnSubmit(app_id) => self
-                .app_serv
-                .exec(&app_id)
+            Message::OnSubmit => self
+                .exec_selected()
                 .inspect_err(|err| {
                     info!("Error exec: {err:?}");
                 })
-                .map(|inner_task| {
-                    inner_task
-                        .map(Message::AppServ)
-                        .chain(Task::done(Message::ExecSuccess))
-                })
+                .map(|inner_task| inner_task.chain(Task::done(Message::ExecSuccess)))
                 .unwrap_or(Task::none()),
             Message::ExecSuccess => Task::none(),
+            Message::AppsChanged(apps) => {
+                let win_task = self
+                    .win_serv
+                    .update(win_serv::Message::LoadApps(apps.clone()))
+                    .map(Message::WinServ);
+                let inner_task = self
+                    .app_serv
+                    .update(app_serv::Message::LoadApps(apps))
+                    .map(Message::AppServ);
+                self.clamp_selected();
+                Task::batch([inner_task, win_task])
+            }
+            Message::ResHovered(nth) => {
+                self.selected = nth;
+                Task::none()
+            }
+            Message::ResClicked(nth) => {
+                self.selected = nth;
+                Task::done(Message::OnSubmit)
+            }
+            Message::ResScrolled(delta) => {
+                let lines = match delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+                };
+                // start over when the direction changes
+                if self.scrolled.signum() != lines.signum() {
+                    self.scrolled = 0.0;
+                }
+                self.scrolled += lines;
+
+                if self.scrolled >= LINES_PER_PAGE {
+                    self.scrolled = 0.0;
+                    self.page_back()
+                } else if self.scrolled <= -LINES_PER_PAGE {
+                    self.scrolled = 0.0;
+                    self.page_forward()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::NextModi => self.switch_modi(self.modi_type.cycle(true)),
+            Message::PrevModi => self.switch_modi(self.modi_type.cycle(false)),
             Message::LeftPressed(captured) => {
                 if !captured {
                     self.page_back()
@@ -222,7 +421,7 @@ impl Comp for Launcher {
                 Task::none()
             }
             Message::DownPressed(_captured) => {
-                self.selected = (self.selected + 1).min(self.app_serv.len() - 1);
+                self.selected = (self.selected + 1).min(self.len().saturating_sub(1));
                 Task::none()
             }
 
@@ -236,14 +435,14 @@ impl Comp for Launcher {
             }
             Message::HKeyPressed(captured) => {
                 if !captured {
-                    self.page_back()
+                    self.collapse_or_page_back()
                 } else {
                     Task::none()
                 }
             }
             Message::JKeyPressed(captured) => {
                 if !captured {
-                    self.selected = (self.selected + 1).min(self.app_serv.len() - 1);
+                    self.selected = (self.selected + 1).min(self.len().saturating_sub(1));
                 }
                 Task::none()
             }
@@ -255,18 +454,61 @@ impl Comp for Launcher {
             }
             Message::LKeyPressed(captured) => {
                 if !captured {
-                    self.page_forward()
+                    self.expand_or_page_forward()
                 } else {
                     Task::none()
                 }
             }
             Message::AppServ(message) => {
-                let inner_task = self.app_serv.update(message.clone()).map(Message::AppServ);
-
-                if matches!(message, app_serv::Message::Query(_)) {
-                    self.selected = self.selected.min(self.app_serv.len().saturating_sub(1));
-                }
-
+                let inner_task = self.app_serv.update(message).map(Message::AppServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::RunServ(message) => {
+                let inner_task = self.run_serv.update(message).map(Message::RunServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::WinServ(message) => {
+                let inner_task = self.win_serv.update(message).map(Message::WinServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::SshServ(message) => {
+                let inner_task = self.ssh_serv.update(message).map(Message::SshServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::CalcServ(message) => match message {},
+            Message::FilesChanged(scan) => {
+                let inner_task = self
+                    .file_serv
+                    .update(file_serv::Message::LoadScan(scan))
+                    .map(Message::FileServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::FileServ(message) => {
+                let inner_task = self.file_serv.update(message).map(Message::FileServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::ClipsChanged(history) => {
+                let inner_task = self
+                    .clip_serv
+                    .update(clip_serv::Message::LoadHistory(history))
+                    .map(Message::ClipServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::ClipServ(message) => {
+                let inner_task = self.clip_serv.update(message).map(Message::ClipServ);
+                self.clamp_selected();
+                inner_task
+            }
+            Message::EmojiServ(message) => {
+                let inner_task = self.emoji_serv.update(message).map(Message::EmojiServ);
+                self.clamp_selected();
                 inner_task
             }
         }
@@ -275,12 +517,7 @@ impl Comp for Launcher {
     fn view(&self) -> iced::Element<'_, Self::Message> {
         let theme = &CAT_THEME;
         let spacing = theme.spacing();
-        let cur_selected = self
-            .app_serv
-            .res()
-            .get(self.selected)
-            .map(|res| res.id.clone())
-            .map(Message::OnSubmit);
+        let cur_selected = (self.selected < self.len()).then_some(Message::OnSubmit);
 
         let prompt = {
             let size = spacing.lg();
@@ -300,7 +537,7 @@ impl Comp for Launcher {
                 .on_input(Message::SearchUpdated)
                 .on_submit_maybe(cur_selected);
 
-            let prompt_type = self.prompt_type.to_string();
+            let prompt_type = self.modi_type.to_string();
             let mode = self.mode.to_string().to_uppercase();
             let mode_color = match self.mode {
                 Mode::Normal => theme.blue(),
@@ -314,9 +551,38 @@ impl Comp for Launcher {
         };
 
         let results = {
-            container(self.view_apps())
-                .height(Length::Fill)
-                .align_y(Vertical::Top)
+            let list = container(match self.modi_type {
+                ModiType::Apps => column![
+                    self.view_calc(self.calc_serv.res()),
+                    self.view_res(self.app_serv.res(), self.calc_serv.len()),
+                ]
+                .spacing(spacing.xs())
+                .into(),
+                ModiType::Run => self.view_res(self.run_serv.res(), 0),
+                ModiType::Windows => self.view_res(self.win_serv.res(), 0),
+                ModiType::Ssh => self.view_res(self.ssh_serv.res(), 0),
+                ModiType::Calc => self.view_calc(self.calc_serv.res()),
+                ModiType::Files => self.view_res(self.file_serv.res(), 0),
+                ModiType::Clipboard => self.view_res(self.clip_serv.res(), 0),
+                ModiType::Emoji => self.view_res(self.emoji_serv.res(), 0),
+            })
+            .width(Length::FillPortion(3))
+            .height(Length::Fill)
+            .align_y(Vertical::Top);
+
+            let preview = (self.modi_type == ModiType::Apps)
+                .then(|| self.selected.checked_sub(self.calc_serv.len()))
+                .flatten()
+                .and_then(|nth| self.app_serv.get_nth(nth))
+                .map(|(app, action)| self.view_preview(app, action));
+
+            mouse_area(
+                row![list]
+                    .push(preview)
+                    .spacing(spacing.md())
+                    .padding(padding::top(spacing.sm())),
+            )
+            .on_scroll(Message::ResScrolled)
         };
 
         let content = column![prompt, results].height(Length::Fill);
@@ -339,27 +605,24 @@ impl Comp for Launcher {
 }
 
 impl Launcher {
-    fn view_apps(&self) -> Element<'static, Message> {
+    /// `offset` is the position of the first result in the list
+    fn view_res<T>(&self, res: &[Res<T>], offset: usize) -> Element<'static, Message> {
         let theme = &CAT_THEME;
         let spacing = theme.spacing();
-        let selected = self
-            .app_serv
-            .res()
-            .get(self.selected)
-            .as_ref()
-            .map(|res| &res.id);
 
-        self.app_serv
-            .res()
-            .iter()
+        res.iter()
+            .enumerate()
             .map(
-                move |Res {
-                          id,
-                          icon,
-                          content,
-                          tooltip,
-                      }| {
-                    let is_selected = selected.is_some_and(|inner_id| inner_id == id);
+                move |(
+                    idx,
+                    Res {
+                        icon,
+                        content,
+                        tooltip,
+                        ..
+                    },
+                )| {
+                    let is_selected = idx + offset == self.selected;
                     let content = align_center!(text!("{content}").size(spacing.lg()));
                     let icon = icon
                         .as_ref()
@@ -383,7 +646,7 @@ impl Launcher {
                                 ..Default::default()
                             })
                     });
-                    align_center!(row![icon, content])
+                    let row = align_center!(row![icon, content])
                         .padding(padding::horizontal(spacing.md()))
                         .style(move |_| container::Style {
                             border: border::width(spacing.xs())
@@ -399,7 +662,11 @@ impl Launcher {
                         })
                         .height(spacing.xl3())
                         .width(Length::Fill)
-                        .maybe_tooltip(Position::Left, maybe_tooltip)
+                        .maybe_tooltip(Position::Left, maybe_tooltip);
+
+                    mouse_area(row)
+                        .on_enter(Message::ResHovered(idx + offset))
+                        .on_press(Message::ResClicked(idx + offset))
                 },
             )
             .fold(Column::new().spacing(spacing.xs()), |col, row| {
@@ -408,34 +675,246 @@ impl Launcher {
             .into()
     }
 
+    /// calculations are listed above the apps, always at the top
+    fn view_calc(&self, res: &[Res<String>]) -> Element<'static, Message> {
+        let theme = &CAT_THEME;
+        let spacing = theme.spacing();
+
+        res.iter()
+            .enumerate()
+            .map(
+                |(
+                    idx,
+                    Res {
+                        content, tooltip, ..
+                    },
+                )| {
+                    let is_selected = idx == self.selected;
+                    let expr = text!("{}", tooltip.clone().unwrap_or_default())
+                        .size(spacing.md())
+                        .color(theme.subtext0());
+                    let result = text!("= {content}").size(spacing.xl()).color(theme.green());
+
+                    let row = container(column![expr, result])
+                        .padding(padding::horizontal(spacing.md()).vertical(spacing.xs()))
+                        .center_y(Length::Shrink)
+                        .style(move |_| container::Style {
+                            background: Some(theme.surface0().into()),
+                            border: border::width(spacing.xs())
+                                .rounded(theme.radius().lg())
+                                .color(if is_selected {
+                                    theme.teal()
+                                } else {
+                                    theme.trans()
+                                }),
+                            ..Default::default()
+                        })
+                        .width(Length::Fill);
+
+                    mouse_area(row)
+                        .on_enter(Message::ResHovered(idx))
+                        .on_press(Message::ResClicked(idx))
+                },
+            )
+            .fold(Column::new().spacing(spacing.xs()), |col, row| {
+                col.push(row)
+            })
+            .into()
+    }
+
+    /// details of the selected app, or of the action with its app
+    fn view_preview(&self, app: &AppDesc, action: Option<&AppAction>) -> Element<'static, Message> {
+        let theme = &CAT_THEME;
+        let spacing = theme.spacing();
+
+        let icon = action
+            .and_then(|action| action.icon.as_ref())
+            .or(app.icon.as_ref())
+            .map(|fdo_icon| fdo_icon.elem(spacing.xl5()));
+        let name = match action {
+            Some(action) => format!("{} › {}", app.name, action.name),
+            None => app.name.clone(),
+        };
+        let gen_name = app.gen_name.as_ref().map(|gen_name| {
+            text!("{gen_name}")
+                .size(spacing.md())
+                .color(theme.subtext0())
+        });
+        let comment = app
+            .comment
+            .as_ref()
+            .map(|comment| text!("{comment}").size(spacing.md()));
+        let categories = app
+            .categories
+            .as_ref()
+            .filter(|categories| !categories.is_empty())
+            .map(|categories| {
+                text!("{}", categories.join(" · "))
+                    .size(spacing.md())
+                    .color(theme.lavender())
+            });
+        let exec = {
+            let argv = action.map(|action| &action.exec).unwrap_or(&app.exec);
+            let prefix = if app.terminal { "term: " } else { "" };
+            text!("{prefix}{}", desktop_exec::join(argv))
+                .size(spacing.md())
+                .color(theme.peach())
+        };
+
+        container(
+            column![]
+                .push(icon)
+                .push(text!("{name}").size(spacing.lg()))
+                .push(gen_name)
+                .push(comment)
+                .push(categories)
+                .push(exec)
+                .spacing(spacing.sm()),
+        )
+        .padding(spacing.md())
+        .style(|_| container::Style {
+            background: Some(theme.surface0().into()),
+            border: border::rounded(theme.radius().lg()),
+            ..Default::default()
+        })
+        .width(Length::FillPortion(2))
+        .into()
+    }
+
+    /// num of results of the current modi
+    fn len(&self) -> usize {
+        match self.modi_type {
+            ModiType::Apps => self.calc_serv.len() + self.app_serv.len(),
+            ModiType::Run => self.run_serv.len(),
+            ModiType::Windows => self.win_serv.len(),
+            ModiType::Ssh => self.ssh_serv.len(),
+            ModiType::Calc => self.calc_serv.len(),
+            ModiType::Files => self.file_serv.len(),
+            ModiType::Clipboard => self.clip_serv.len(),
+            ModiType::Emoji => self.emoji_serv.len(),
+        }
+    }
+
+    /// num of results of the current modi across all pages
+    fn total(&self) -> usize {
+        match self.modi_type {
+            ModiType::Apps => self.calc_serv.total() + self.app_serv.total(),
+            ModiType::Run => self.run_serv.total(),
+            ModiType::Windows => self.win_serv.total(),
+            ModiType::Ssh => self.ssh_serv.total(),
+            ModiType::Calc => self.calc_serv.total(),
+            ModiType::Files => self.file_serv.total(),
+            ModiType::Clipboard => self.clip_serv.total(),
+            ModiType::Emoji => self.emoji_serv.total(),
+        }
+    }
+
+    fn clamp_selected(&mut self) {
+        self.selected = self.selected.min(self.len().saturating_sub(1));
+    }
+
+    /// query the current modi with the search and page
+    fn query(&mut self) -> Task<Message> {
+        let query = Query::new(
+            if self.search.is_empty() {
+                None
+            } else {
+                Some(self.search.clone())
+            },
+            self.page,
+            NUM_OF_ITEMS,
+        );
+
+        let task = match self.modi_type {
+            ModiType::Apps => Task::batch([
+                self.calc_serv.query(query.clone()).map(Message::CalcServ),
+                self.app_serv.query(query).map(Message::AppServ),
+            ]),
+            ModiType::Run => self.run_serv.query(query).map(Message::RunServ),
+            ModiType::Windows => self.win_serv.query(query).map(Message::WinServ),
+            ModiType::Ssh => self.ssh_serv.query(query).map(Message::SshServ),
+            ModiType::Calc => self.calc_serv.query(query).map(Message::CalcServ),
+            ModiType::Files => self.file_serv.query(query).map(Message::FileServ),
+            ModiType::Clipboard => self.clip_serv.query(query).map(Message::ClipServ),
+            ModiType::Emoji => self.emoji_serv.query(query).map(Message::EmojiServ),
+        };
+        self.clamp_selected();
+
+        task
+    }
+
+    /// exec the selected result of the current modi
+    fn exec_selected(&mut self) -> anyhow::Result<Task<Message>> {
+        let selected = self.selected;
+
+        Ok(match self.modi_type {
+            ModiType::Apps => match selected.checked_sub(self.calc_serv.len()) {
+                Some(nth) => exec_nth(&mut self.app_serv, nth)?.map(Message::AppServ),
+                None => exec_nth(&mut self.calc_serv, selected)?.map(Message::CalcServ),
+            },
+            ModiType::Run => exec_nth(&mut self.run_serv, selected)?.map(Message::RunServ),
+            ModiType::Windows => exec_nth(&mut self.win_serv, selected)?.map(Message::WinServ),
+            ModiType::Ssh => exec_nth(&mut self.ssh_serv, selected)?.map(Message::SshServ),
+            ModiType::Calc => exec_nth(&mut self.calc_serv, selected)?.map(Message::CalcServ),
+            ModiType::Files => exec_nth(&mut self.file_serv, selected)?.map(Message::FileServ),
+            ModiType::Clipboard => exec_nth(&mut self.clip_serv, selected)?.map(Message::ClipServ),
+            ModiType::Emoji => exec_nth(&mut self.emoji_serv, selected)?.map(Message::EmojiServ),
+        })
+    }
+
+    fn switch_modi(&mut self, modi_type: ModiType) -> Task<Message> {
+        self.modi_type = modi_type;
+        self.page = 0;
+        self.selected = 0;
+        self.query()
+    }
+
+    /// up to the last page
     fn page_forward(&mut self) -> Task<Message> {
+        let last_page = self.total().saturating_sub(1) / NUM_OF_ITEMS;
+        if self.page >= last_page {
+            return Task::none();
+        }
         self.page += 1;
-        self.app_serv
-            .query(Query::new(
-                if self.search.is_empty() {
-                    None
-                } else {
-                    Some(self.search.clone())
-                },
-                self.page,
-                NUM_OF_ITEMS,
-            ))
-            .map(Message::AppServ)
+        self.query()
     }
 
     fn page_back(&mut self) -> Task<Message> {
         self.page = self.page.saturating_sub(1);
-        self.app_serv
-            .query(Query::new(
-                if self.search.is_empty() {
-                    None
-                } else {
-                    Some(self.search.clone())
-                },
-                self.page,
-                NUM_OF_ITEMS,
-            ))
-            .map(Message::AppServ)
+        self.query()
+    }
+
+    /// list the actions of the selected app, or page forward if it has none
+    fn expand_or_page_forward(&mut self) -> Task<Message> {
+        if self.modi_type == ModiType::Apps
+            && let Some(nth) = self.selected.checked_sub(self.calc_serv.len())
+            && self.app_serv.expand(nth)
+        {
+            return Task::none();
+        }
+        self.page_forward()
+    }
+
+    /// hide the listed actions, or page back if none are listed
+    fn collapse_or_page_back(&mut self) -> Task<Message> {
+        if self.modi_type == ModiType::Apps
+            && let Some(selected) = self.app_serv.collapse()
+        {
+            self.selected = selected + self.calc_serv.len();
+            return Task::none();
+        }
+        self.page_back()
+    }
+}
+
+fn exec_nth<M>(modi: &mut M, nth: usize) -> anyhow::Result<Task<M::Message>>
+where
+    M: Modi,
+    M::Id: Clone,
+{
+    match modi.res().get(nth).map(|res| res.id.clone()) {
+        Some(id) => modi.exec(&id),
+        None => Ok(Task::none()),
     }
 }
 
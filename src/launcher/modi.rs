@@ -1,7 +1,18 @@
+use std::{
+    cmp, env,
+    ffi::{OsStr, OsString},
+    path::Path,
+};
+
 use derive_more::Constructor;
 use iced::Task;
+use itertools::Itertools;
+use nucleo_matcher::{
+    Config, Matcher, Utf32Str,
+    pattern::{CaseMatching, Normalization, Pattern},
+};
 
-use crate::widget::fdo_icons::FdIcon;
+use crate::{config::CONFIG, widget::fdo_icons::FdIcon};
 
 #[derive(Debug, Clone, Constructor)]
 pub struct Res<T> {
@@ -26,6 +37,10 @@ pub trait Modi {
     #[must_use]
     fn len(&self) -> usize;
 
+    /// num of results across all pages
+    #[must_use]
+    fn total(&self) -> usize;
+
     /// the results of the last query
     #[must_use]
     fn res(&self) -> &Vec<Res<Self::Id>>;
@@ -37,3 +52,86 @@ pub trait Modi {
     /// update internal state such as exec count or history
     fn exec(&mut self, id: &Self::Id) -> anyhow::Result<Task<Self::Message>>;
 }
+
+/// fuzzy score items on weighted haystacks, an item scores its best match
+/// times the weight of that haystack, items that don't match are dropped
+pub fn score_by<T, const N: usize>(
+    term: &str,
+    items: Vec<T>,
+    haystacks: impl Fn(&T) -> [(Option<&str>, u32); N],
+) -> Vec<(T, u32)> {
+    let pattern = Pattern::parse(term, CaseMatching::Ignore, Normalization::Smart);
+
+    if pattern.atoms.is_empty() {
+        return items.into_iter().map(|item| (item, 0)).collect();
+    }
+
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut buff = Vec::new();
+
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let score = haystacks(&item)
+                .into_iter()
+                .filter_map(|(haystack, weight)| {
+                    let haystack = Utf32Str::new(haystack?, &mut buff);
+                    let score = pattern.score(haystack, &mut matcher)?;
+                    Some(score.saturating_mul(weight))
+                })
+                .max()?;
+            Some((item, score))
+        })
+        .collect()
+}
+
+/// fuzzy match items on weighted haystacks, best match first
+pub fn match_by<T, const N: usize>(
+    term: &str,
+    items: Vec<T>,
+    haystacks: impl Fn(&T) -> [(Option<&str>, u32); N],
+) -> Vec<T> {
+    score_by(term, items, haystacks)
+        .into_iter()
+        .sorted_by_key(|(_, score)| cmp::Reverse(*score))
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// spawn a detached process, inside the terminal emulator if asked
+pub fn spawn<S: AsRef<OsStr>>(
+    argv: impl IntoIterator<Item = S>,
+    cwd: Option<&Path>,
+    in_terminal: bool,
+) -> anyhow::Result<()> {
+    let terminal = if in_terminal { terminal() } else { Vec::new() };
+    let mut parts = terminal
+        .into_iter()
+        .map(OsString::from)
+        .chain(argv.into_iter().map(|arg| arg.as_ref().to_owned()));
+
+    let Some(cmd) = parts.next() else {
+        anyhow::bail!("Empty command");
+    };
+
+    let mut command = tokio::process::Command::new(cmd);
+    command.args(parts).process_group(0);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    command.spawn()?;
+
+    Ok(())
+}
+
+/// the terminal command from the config, then $TERMINAL, then xdg-terminal-exec
+fn terminal() -> Vec<String> {
+    if let Some(terminal) = &CONFIG.launcher.terminal {
+        return terminal.clone();
+    }
+
+    match env::var("TERMINAL") {
+        Ok(terminal) => vec![terminal, "-e".to_owned()],
+        Err(_) => vec!["xdg-terminal-exec".to_owned()],
+    }
+}
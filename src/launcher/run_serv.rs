@@ -0,0 +1,249 @@
+use std::{cmp, collections::BTreeMap, os::unix::fs::PermissionsExt, path::PathBuf};
+
+use derive_more::{Deref, DerefMut, From};
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use itertools::Itertools;
+use tokio::fs;
+use tracing::info;
+
+use crate::{
+    feature::Service,
+    launcher::{
+        app_serv::get_bin_dirs,
+        frecency::FrecencyMap,
+        modi::{self, Modi, Query, Res},
+    },
+};
+
+/// command lines starting with this are run inside the terminal
+const TERMINAL_PREFIX: char = '!';
+const HISTORY_FILE: &str = "launcher_run_history.json";
+
+/// executables in $PATH, keyed by name
+#[derive(Debug, Deref, DerefMut, From, Clone, Default)]
+pub struct BinMap(BTreeMap<String, PathBuf>);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadBins(BinMap),
+    LoadHistory(FrecencyMap),
+}
+
+pub struct RunServ {
+    bins: BinMap,
+    /// previously run command lines
+    history: FrecencyMap,
+    last_query: Query,
+    res: Vec<Res<String>>,
+    /// num of results across all pages
+    total: usize,
+}
+
+impl Service for RunServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let init_bins = Task::future(async {
+            get_bins()
+                .await
+                .inspect_err(|err| {
+                    info!("Error loading bins: {err:?}");
+                })
+                .unwrap_or_default()
+        })
+        .map(Message::LoadBins);
+        let init_history = Task::future(async {
+            FrecencyMap::load(FrecencyMap::get_path(HISTORY_FILE))
+                .await
+                .inspect_err(|err| {
+                    info!("Error loading run history: {err:?}");
+                })
+                .unwrap_or_default()
+        })
+        .map(Message::LoadHistory);
+
+        (
+            Self {
+                bins: BinMap::default(),
+                history: FrecencyMap::default(),
+                last_query: Query::new(None, 0, 10),
+                res: Vec::new(),
+                total: 0,
+            },
+            Task::batch([init_bins, init_history]).map(f),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::LoadBins(bins) => {
+                self.bins = bins;
+                self.query(self.last_query.clone())
+            }
+            Message::LoadHistory(history) => {
+                self.history = history;
+                self.query(self.last_query.clone())
+            }
+        }
+    }
+}
+
+impl Modi for RunServ {
+    type Id = String;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+        let Query { term, page, limit } = query;
+        let line = term.as_deref().unwrap_or_default().trim();
+        let (in_terminal, cmd) = split_terminal(line);
+
+        let history = {
+            let lines: Vec<_> = self.history.keys().map(String::as_str).collect();
+            let lines = if line.is_empty() {
+                lines
+            } else {
+                modi::match_by(line, lines, |line| [(Some(*line), 1)])
+            };
+
+            lines
+                .into_iter()
+                .sorted_by_key(|line| cmp::Reverse(self.history.score(line)))
+                .map(|line| {
+                    let count = self.history.get(line).map(|f| f.count).unwrap_or_default();
+                    (line.to_owned(), format!("runs: {count}"))
+                })
+        };
+
+        // complete the command name until args are typed
+        let bins = {
+            let names: Vec<_> = if cmd.contains(char::is_whitespace) {
+                Vec::new()
+            } else if cmd.is_empty() {
+                self.bins.iter().collect()
+            } else {
+                modi::match_by(cmd, self.bins.iter().collect(), |(name, _)| {
+                    [(Some(name.as_str()), 1)]
+                })
+            };
+
+            names.into_iter().map(move |(name, path)| {
+                let line = if in_terminal {
+                    format!("{TERMINAL_PREFIX}{name}")
+                } else {
+                    name.clone()
+                };
+                (line, path.display().to_string())
+            })
+        };
+
+        // run the typed line as is
+        let typed = (!cmd.is_empty()).then(|| {
+            let tooltip = if in_terminal {
+                "run in terminal"
+            } else {
+                "run"
+            };
+            (line.to_owned(), tooltip.to_owned())
+        });
+
+        let lines: Vec<_> = typed
+            .into_iter()
+            .chain(history)
+            .chain(bins)
+            .unique_by(|(line, _)| line.clone())
+            .collect();
+
+        self.total = lines.len();
+        self.res = lines
+            .into_iter()
+            .skip(page * limit)
+            .take(limit)
+            .map(|(line, tooltip)| Res::new(line.clone(), None, line, Some(tooltip)))
+            .collect();
+
+        Task::none()
+    }
+
+    fn exec(&mut self, line: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        let line = line.trim();
+        let (in_terminal, cmd) = split_terminal(line);
+        if cmd.is_empty() {
+            return Ok(Task::none());
+        }
+
+        modi::spawn(["sh", "-c", cmd], None, in_terminal)?;
+        self.history.on_exec(line);
+
+        self.history
+            .jsonify()
+            .map(|json_str| FrecencyMap::save(FrecencyMap::get_path(HISTORY_FILE), json_str))
+            .map(Task::future)
+            .map(Task::discard)
+    }
+}
+
+/// split off the terminal prefix of a command line
+fn split_terminal(line: &str) -> (bool, &str) {
+    match line.strip_prefix(TERMINAL_PREFIX) {
+        Some(cmd) => (true, cmd.trim_start()),
+        None => (false, line),
+    }
+}
+
+/// index the executables of every dir in $PATH
+/// dirs that can't be read are skipped, so one doesn't hide the rest
+async fn get_bins() -> anyhow::Result<BinMap> {
+    let mut bins = BinMap::default();
+
+    for bin_dir in get_bin_dirs()? {
+        let mut entries = match fs::read_dir(&bin_dir).await {
+            Ok(entries) => entries,
+            Err(err) => {
+                info!("Error reading bin dir {bin_dir:?}: {err:?}");
+                continue;
+            }
+        };
+
+        loop {
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(err) => {
+                    info!("Error reading bin dir {bin_dir:?}: {err:?}");
+                    break;
+                }
+            };
+            let path = entry.path();
+            let is_exec = fs::metadata(&path)
+                .await
+                .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0);
+
+            if is_exec && let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                bins.entry(name.to_owned()).or_insert(path);
+            }
+        }
+    }
+
+    Ok(bins)
+}
@@ -0,0 +1,187 @@
+use std::path::PathBuf;
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use tokio::fs;
+use tracing::info;
+
+use crate::{
+    feature::Service,
+    launcher::modi::{self, Modi, Query, Res},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct SshHost {
+    pub name: String,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LoadHosts(Vec<SshHost>),
+}
+
+/// hosts from ~/.ssh/config
+pub struct SshServ {
+    hosts: Vec<SshHost>,
+    last_query: Query,
+    res: Vec<Res<String>>,
+    /// num of results across all pages
+    total: usize,
+}
+
+impl Service for SshServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let init_hosts = Task::future(async {
+            get_hosts()
+                .await
+                .inspect_err(|err| {
+                    info!("Error loading ssh hosts: {err:?}");
+                })
+                .unwrap_or_default()
+        })
+        .map(Message::LoadHosts);
+
+        (
+            Self {
+                hosts: Vec::new(),
+                last_query: Query::new(None, 0, 10),
+                res: Vec::new(),
+                total: 0,
+            },
+            init_hosts.map(f),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::none()
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::LoadHosts(hosts) => {
+                self.hosts = hosts;
+                self.query(self.last_query.clone())
+            }
+        }
+    }
+}
+
+impl Modi for SshServ {
+    type Id = String;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+        let Query { term, page, limit } = query;
+
+        let mut hosts: Vec<_> = self.hosts.iter().collect();
+        if let Some(term) = term {
+            hosts = modi::match_by(&term, hosts, |host| [(Some(&host.name), 1)]);
+        }
+
+        self.total = hosts.len();
+
+        self.res = hosts
+            .into_iter()
+            .skip(page * limit)
+            .take(limit)
+            .map(Res::from)
+            .collect();
+
+        Task::none()
+    }
+
+    fn exec(&mut self, name: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        modi::spawn(["ssh", name], None, true)?;
+        Ok(Task::none())
+    }
+}
+
+impl From<&SshHost> for Res<String> {
+    fn from(host: &SshHost) -> Self {
+        let tooltip = host.hostname.as_ref().map(|hostname| match &host.user {
+            Some(user) => format!("{user}@{hostname}"),
+            None => hostname.clone(),
+        });
+
+        Res::new(host.name.clone(), None, host.name.clone(), tooltip)
+    }
+}
+
+fn get_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or(PathBuf::from("."))
+        .join(".ssh/config")
+}
+
+/// parse the concrete hosts out of the ssh config, skipping wildcard patterns
+async fn get_hosts() -> anyhow::Result<Vec<SshHost>> {
+    let path = get_path();
+    if !fs::try_exists(&path).await? {
+        return Ok(Vec::new());
+    }
+
+    let config = fs::read_to_string(&path).await?;
+    let mut hosts: Vec<SshHost> = Vec::new();
+    // hosts declared by the current `Host` block
+    let mut block = 0..0;
+
+    for line in config.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line
+            .split_once(|c: char| c.is_whitespace() || c == '=')
+            .map(|(key, value)| (key, value.trim_start_matches(['=', ' ', '\t']).trim()))
+        else {
+            continue;
+        };
+
+        match key.to_lowercase().as_str() {
+            "host" => {
+                let start = hosts.len();
+                hosts.extend(
+                    value
+                        .split_whitespace()
+                        .filter(|name| !name.contains(['*', '?', '!']))
+                        .map(|name| SshHost {
+                            name: name.to_owned(),
+                            ..Default::default()
+                        }),
+                );
+                block = start..hosts.len();
+            }
+            "match" => block = hosts.len()..hosts.len(),
+            "hostname" => hosts[block.clone()]
+                .iter_mut()
+                .for_each(|host| host.hostname = Some(value.to_owned())),
+            "user" => hosts[block.clone()]
+                .iter_mut()
+                .for_each(|host| host.user = Some(value.to_owned())),
+            _ => (),
+        }
+    }
+
+    Ok(hosts)
+}
@@ -0,0 +1,118 @@
+//! symbols that aren't emoji, as (glyph, name, keywords)
+pub const SYMBOLS: &[(&str, &str, &str)] = &[
+    // arrows
+    ("←", "leftwards arrow", "left arrow"),
+    ("→", "rightwards arrow", "right arrow"),
+    ("↑", "upwards arrow", "up arrow"),
+    ("↓", "downwards arrow", "down arrow"),
+    ("↔", "left right arrow", "arrow both"),
+    ("↕", "up down arrow", "arrow both"),
+    ("⇐", "leftwards double arrow", "left arrow implied"),
+    ("⇒", "rightwards double arrow", "right arrow implies"),
+    ("⇔", "left right double arrow", "iff equivalent"),
+    ("↵", "downwards arrow with corner leftwards", "enter return"),
+    ("⏎", "return symbol", "enter return"),
+    ("↩", "leftwards arrow with hook", "undo back"),
+    ("↪", "rightwards arrow with hook", "redo forward"),
+    ("⇥", "rightwards arrow to bar", "tab"),
+    ("⇧", "upwards white arrow", "shift"),
+    // keys
+    ("⌘", "place of interest sign", "command cmd key"),
+    ("⌥", "option key", "alt key"),
+    ("⌃", "up arrowhead", "control ctrl key"),
+    ("⌫", "erase to the left", "backspace delete key"),
+    ("⎋", "broken circle with northwest arrow", "escape esc key"),
+    // math
+    ("±", "plus minus sign", "math"),
+    ("×", "multiplication sign", "times math"),
+    ("÷", "division sign", "divide math"),
+    ("≈", "almost equal to", "approx math"),
+    ("≠", "not equal to", "math"),
+    ("≤", "less than or equal to", "lte math"),
+    ("≥", "greater than or equal to", "gte math"),
+    ("∞", "infinity", "math"),
+    ("√", "square root", "sqrt math"),
+    ("∑", "n-ary summation", "sum sigma math"),
+    ("∏", "n-ary product", "product pi math"),
+    ("∫", "integral", "math"),
+    ("∂", "partial differential", "math"),
+    ("∆", "increment", "delta math"),
+    ("∇", "nabla", "del gradient math"),
+    ("∈", "element of", "in set math"),
+    ("∉", "not an element of", "not in set math"),
+    ("∅", "empty set", "null set math"),
+    ("∩", "intersection", "set math"),
+    ("∪", "union", "set math"),
+    ("⊂", "subset of", "set math"),
+    ("∀", "for all", "forall logic math"),
+    ("∃", "there exists", "exists logic math"),
+    ("¬", "not sign", "logic negation"),
+    ("∧", "logical and", "logic wedge"),
+    ("∨", "logical or", "logic vee"),
+    ("°", "degree sign", "temperature angle"),
+    ("‰", "per mille sign", "permille"),
+    ("½", "vulgar fraction one half", "half fraction"),
+    ("¼", "vulgar fraction one quarter", "quarter fraction"),
+    ("¾", "vulgar fraction three quarters", "fraction"),
+    ("²", "superscript two", "squared power"),
+    ("³", "superscript three", "cubed power"),
+    // greek
+    ("α", "greek small letter alpha", "alpha"),
+    ("β", "greek small letter beta", "beta"),
+    ("γ", "greek small letter gamma", "gamma"),
+    ("δ", "greek small letter delta", "delta"),
+    ("ε", "greek small letter epsilon", "epsilon"),
+    ("θ", "greek small letter theta", "theta"),
+    ("λ", "greek small letter lambda", "lambda"),
+    ("μ", "greek small letter mu", "mu micro"),
+    ("π", "greek small letter pi", "pi"),
+    ("σ", "greek small letter sigma", "sigma"),
+    ("τ", "greek small letter tau", "tau"),
+    ("φ", "greek small letter phi", "phi"),
+    ("ω", "greek small letter omega", "omega"),
+    ("Ω", "greek capital letter omega", "omega ohm"),
+    // currency
+    ("€", "euro sign", "currency money"),
+    ("£", "pound sign", "currency money sterling"),
+    ("¥", "yen sign", "currency money yuan"),
+    ("₿", "bitcoin sign", "currency money btc"),
+    ("¢", "cent sign", "currency money"),
+    // typography
+    ("—", "em dash", "dash hyphen"),
+    ("–", "en dash", "dash hyphen range"),
+    ("…", "horizontal ellipsis", "dots ellipsis"),
+    ("•", "bullet", "dot list"),
+    ("·", "middle dot", "dot interpunct"),
+    ("“", "left double quotation mark", "quote"),
+    ("”", "right double quotation mark", "quote"),
+    ("‘", "left single quotation mark", "quote"),
+    ("’", "right single quotation mark", "quote apostrophe"),
+    (
+        "«",
+        "left-pointing double angle quotation mark",
+        "guillemet quote",
+    ),
+    (
+        "»",
+        "right-pointing double angle quotation mark",
+        "guillemet quote",
+    ),
+    ("§", "section sign", "paragraph law"),
+    ("¶", "pilcrow sign", "paragraph"),
+    ("†", "dagger", "footnote"),
+    ("©", "copyright sign", "copyright"),
+    ("®", "registered sign", "trademark"),
+    ("™", "trade mark sign", "trademark tm"),
+    // misc
+    ("✓", "check mark", "tick done yes"),
+    ("✗", "ballot x", "cross no"),
+    ("★", "black star", "star favorite"),
+    ("☆", "white star", "star favorite"),
+    ("♥", "black heart suit", "heart love"),
+    ("♪", "eighth note", "music note"),
+    ("☐", "ballot box", "checkbox todo"),
+    ("☑", "ballot box with check", "checkbox done"),
+    ("⌀", "diameter sign", "diameter"),
+    ("␣", "open box", "space"),
+    ("¯\\_(ツ)_/¯", "shrug", "kaomoji whatever"),
+];
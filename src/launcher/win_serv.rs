@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use itertools::Itertools;
+use niri_ipc::Action;
+use tracing::info;
+
+use crate::{
+    feature::Service,
+    launcher::{
+        app_serv::AppNameToAppMap,
+        modi::{self, Modi, Query, Res},
+    },
+    niri::{
+        action,
+        state_serv::{self, NiriStateServ, WinId, Window},
+    },
+    widget::fdo_icons::{self, FdIcon},
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Niri(state_serv::Message),
+    LoadIcons(Vec<(String, Option<FdIcon>)>),
+    LoadApps(AppNameToAppMap),
+}
+
+/// a niri window with the info needed to display and match it
+#[derive(Debug, Clone)]
+struct WinDesc<'a> {
+    win: &'a Window,
+    title: &'a str,
+    app_id: &'a str,
+    ws_idx: Option<u8>,
+    /// workspace index to match on
+    ws: Option<String>,
+}
+
+/// open niri windows
+pub struct WinServ {
+    niri: NiriStateServ,
+    /// desktop id to the icon of its entry, app ids usually match it
+    app_icons: HashMap<String, FdIcon>,
+    /// app id to icon, looked up once per app
+    icons: HashMap<String, Option<FdIcon>>,
+    last_query: Query,
+    res: Vec<Res<WinId>>,
+    /// num of results across all pages
+    total: usize,
+}
+
+impl Service for WinServ {
+    type Message = Message;
+    /// the apps from the index
+    type Init = AppNameToAppMap;
+
+    fn new<O: MaybeSend + 'static>(
+        apps: Self::Init,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        let (niri, niri_task) = NiriStateServ::new((), Message::Niri);
+        (
+            Self {
+                niri,
+                app_icons: app_icons(&apps),
+                icons: HashMap::new(),
+                last_query: Query::new(None, 0, 10),
+                res: Vec::new(),
+                total: 0,
+            },
+            niri_task.map(f),
+        )
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        self.niri.subscription().map(Message::Niri)
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::Niri(message) => {
+                let inner_task = self.niri.update(message).map(Message::Niri);
+                let icons_task = self.load_icons();
+                Task::batch([inner_task, icons_task]).chain(self.query(self.last_query.clone()))
+            }
+            Message::LoadIcons(icons) => {
+                self.icons.extend(icons);
+                self.query(self.last_query.clone())
+            }
+            Message::LoadApps(apps) => {
+                self.app_icons = app_icons(&apps);
+                // look them up again against the new entries
+                self.icons.clear();
+                self.load_icons()
+            }
+        }
+    }
+}
+
+impl Modi for WinServ {
+    type Id = WinId;
+    type Message = Message;
+
+    fn len(&self) -> usize {
+        self.res.len()
+    }
+
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    fn res(&self) -> &Vec<Res<Self::Id>> {
+        &self.res
+    }
+
+    fn query(&mut self, query: Query) -> Task<Self::Message> {
+        self.last_query = query.clone();
+        let Query { term, page, limit } = query;
+
+        let mut wins: Vec<_> = self
+            .niri
+            .iter_win()
+            .map(|win| {
+                let ws_idx = win
+                    .ws_id
+                    .as_ref()
+                    .and_then(|ws_id| self.niri.iter_ws().find(|ws| &ws.id == ws_id))
+                    .map(|ws| ws.idx.get());
+
+                WinDesc {
+                    win,
+                    title: win.title.as_deref().unwrap_or_default(),
+                    app_id: win.app_id.as_deref().unwrap_or_default(),
+                    ws_idx,
+                    ws: ws_idx.map(|ws_idx| ws_idx.to_string()),
+                }
+            })
+            .sorted_by_key(|desc| (desc.ws_idx, desc.win.col_idx.clone(), desc.win.id.get()))
+            .collect();
+
+        if let Some(term) = term {
+            wins = modi::match_by(&term, wins, |desc| {
+                [
+                    (Some(desc.title), 120),
+                    (Some(desc.app_id), 100),
+                    (desc.ws.as_deref(), 80),
+                ]
+            });
+        }
+
+        self.total = wins.len();
+
+        self.res = wins
+            .into_iter()
+            .skip(page * limit)
+            .take(limit)
+            .map(|desc| {
+                let icon = self.icons.get(desc.app_id).cloned().flatten();
+                let content = match desc.ws_idx {
+                    Some(ws_idx) => format!("{ws_idx} | {}", desc.title),
+                    None => desc.title.to_owned(),
+                };
+
+                Res::new(
+                    desc.win.id.clone(),
+                    icon,
+                    content,
+                    Some(desc.app_id.to_owned()),
+                )
+            })
+            .collect();
+
+        Task::none()
+    }
+
+    fn exec(&mut self, id: &Self::Id) -> anyhow::Result<Task<Self::Message>> {
+        let id = id.get();
+        Ok(Task::future(async move {
+            if let Err(err) = action::send(Action::FocusWindow { id }).await {
+                info!("Error focusing window: {err:?}");
+            }
+        })
+        .discard())
+    }
+}
+
+impl WinServ {
+    /// look up the icons of app ids not seen before
+    fn load_icons(&mut self) -> Task<Message> {
+        let app_ids: Vec<_> = self
+            .niri
+            .iter_win()
+            .filter_map(|win| win.app_id.clone())
+            .filter(|app_id| !self.icons.contains_key(app_id))
+            .unique()
+            .collect();
+
+        if app_ids.is_empty() {
+            return Task::none();
+        }
+
+        // mark as pending so following events don't look them up again
+        self.icons
+            .extend(app_ids.iter().map(|app_id| (app_id.clone(), None)));
+
+        let app_ids: Vec<_> = app_ids
+            .into_iter()
+            .map(|app_id| {
+                let icon = self
+                    .app_icons
+                    .get(&app_id)
+                    .or_else(|| self.app_icons.get(&app_id.to_lowercase()))
+                    .cloned();
+                (app_id, icon)
+            })
+            .collect();
+
+        Task::future(async move {
+            tokio::task::spawn_blocking(move || {
+                app_ids
+                    .into_iter()
+                    .map(|(app_id, icon)| {
+                        // the icon of the desktop entry, else one named after the app id
+                        let icon = icon
+                            .or_else(|| fdo_icons::find(&app_id))
+                            .or_else(|| fdo_icons::find(&app_id.to_lowercase()));
+                        (app_id, icon)
+                    })
+                    .collect()
+            })
+            .await
+            .inspect_err(|err| {
+                info!("Error loading window icons: {err:?}");
+            })
+            .unwrap_or_default()
+        })
+        .map(Message::LoadIcons)
+    }
+}
+
+/// desktop id to the icon of the apps with one
+fn app_icons(apps: &AppNameToAppMap) -> HashMap<String, FdIcon> {
+    apps.iter()
+        .filter_map(|(app_id, app)| Some((app_id.clone(), app.icon.clone()?)))
+        .collect()
+}
@@ -0,0 +1,139 @@
+//! turns wl_keyboard keycodes into password input with xkbcommon
+use std::{
+    ffi::{CString, c_char},
+    fs::File,
+    io::Read,
+    os::fd::OwnedFd,
+    ptr,
+};
+
+use anyhow::{Context, bail};
+use xkbcommon_dl::{
+    XKB_MOD_NAME_CTRL, XkbCommon, keysyms, xkb_context, xkb_context_flags, xkb_keymap,
+    xkb_keymap_compile_flags, xkb_keymap_format, xkb_state, xkb_state_component,
+};
+
+/// what a key press does to the password
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInput {
+    /// a char, not a string, so nothing is left behind in the heap
+    Char(char),
+    Submit,
+    Backspace,
+    /// escape or ctrl-u
+    Clear,
+    None,
+}
+
+pub struct Keyboard {
+    xkb: &'static XkbCommon,
+    context: *mut xkb_context,
+    keymap: *mut xkb_keymap,
+    state: *mut xkb_state,
+}
+
+impl Keyboard {
+    pub fn new() -> anyhow::Result<Self> {
+        let xkb = xkbcommon_dl::xkbcommon_option().context("libxkbcommon not found")?;
+        let context = unsafe { (xkb.xkb_context_new)(xkb_context_flags::XKB_CONTEXT_NO_FLAGS) };
+        if context.is_null() {
+            bail!("Failed to create xkb context");
+        }
+
+        Ok(Self {
+            xkb,
+            context,
+            keymap: ptr::null_mut(),
+            state: ptr::null_mut(),
+        })
+    }
+
+    /// load the xkb v1 keymap the compositor sent
+    pub fn set_keymap(&mut self, fd: OwnedFd, size: u32) -> anyhow::Result<()> {
+        let mut keymap = Vec::with_capacity(size as usize);
+        File::from(fd).take(size as u64).read_to_end(&mut keymap)?;
+        // the keymap is sent nul terminated
+        let keymap =
+            CString::from_vec_with_nul(keymap).or_else(|err| CString::new(err.into_bytes()))?;
+
+        self.free();
+        unsafe {
+            self.keymap = (self.xkb.xkb_keymap_new_from_string)(
+                self.context,
+                keymap.as_ptr(),
+                xkb_keymap_format::XKB_KEYMAP_FORMAT_TEXT_V1,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            if self.keymap.is_null() {
+                bail!("Failed to compile keymap");
+            }
+            self.state = (self.xkb.xkb_state_new)(self.keymap);
+        }
+        Ok(())
+    }
+
+    pub fn update_modifiers(&mut self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        if self.state.is_null() {
+            return;
+        }
+        unsafe {
+            (self.xkb.xkb_state_update_mask)(self.state, depressed, latched, locked, 0, 0, group);
+        }
+    }
+
+    /// `key` is the evdev code from wl_keyboard
+    pub fn press(&mut self, key: u32) -> KeyInput {
+        if self.state.is_null() {
+            return KeyInput::None;
+        }
+        // xkb keycodes are offset from evdev by 8
+        let keycode = key + 8;
+
+        let (sym, is_ctrl) = unsafe {
+            (
+                (self.xkb.xkb_state_key_get_one_sym)(self.state, keycode),
+                (self.xkb.xkb_state_mod_name_is_active)(
+                    self.state,
+                    XKB_MOD_NAME_CTRL.as_ptr() as *const c_char,
+                    xkb_state_component::XKB_STATE_MODS_EFFECTIVE,
+                ) == 1,
+            )
+        };
+
+        match sym {
+            keysyms::Return | keysyms::KP_Enter => KeyInput::Submit,
+            keysyms::BackSpace => KeyInput::Backspace,
+            keysyms::Escape => KeyInput::Clear,
+            keysyms::u if is_ctrl => KeyInput::Clear,
+            _ if is_ctrl => KeyInput::None,
+            _ => {
+                let codepoint = unsafe { (self.xkb.xkb_state_key_get_utf32)(self.state, keycode) };
+
+                match char::from_u32(codepoint).filter(|ch| *ch != '\0' && !ch.is_control()) {
+                    Some(ch) => KeyInput::Char(ch),
+                    None => KeyInput::None,
+                }
+            }
+        }
+    }
+
+    fn free(&mut self) {
+        unsafe {
+            if !self.state.is_null() {
+                (self.xkb.xkb_state_unref)(self.state);
+            }
+            if !self.keymap.is_null() {
+                (self.xkb.xkb_keymap_unref)(self.keymap);
+            }
+        }
+        self.state = ptr::null_mut();
+        self.keymap = ptr::null_mut();
+    }
+}
+
+impl Drop for Keyboard {
+    fn drop(&mut self) {
+        self.free();
+        unsafe { (self.xkb.xkb_context_unref)(self.context) };
+    }
+}
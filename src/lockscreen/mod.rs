@@ -0,0 +1,126 @@
+//! built-in lock screen, shown when logind asks the session to lock
+//! lock surfaces can't be iced windows, so it draws itself on its own
+//! wayland connection
+mod keyboard;
+mod pam;
+mod password;
+mod render;
+mod session_lock;
+
+use std::{
+    ffi::CStr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use tracing::{error as log_err, info};
+
+use crate::{
+    config::CONFIG,
+    feature::Service,
+    logind::{self, LockSignal},
+};
+
+const PAM_SERVICE: &str = "login";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Lock,
+    Unlock,
+    /// the daemon shows the error
+    Unlocked(Result<(), String>),
+}
+
+pub struct LockServ {
+    /// set to unlock without a password, Some while locked
+    unlock: Option<Arc<AtomicBool>>,
+}
+
+impl LockServ {
+    pub fn is_locked(&self) -> bool {
+        self.unlock.is_some()
+    }
+}
+
+impl Service for LockServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        (Self { unlock: None }, Task::none())
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::run(logind::watch_lock).map(|signal| match signal {
+            LockSignal::Lock => Message::Lock,
+            LockSignal::Unlock => Message::Unlock,
+        })
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::Lock => {
+                if self.is_locked() {
+                    return Task::none();
+                }
+                let unlock = Arc::new(AtomicBool::new(false));
+                self.unlock = Some(unlock.clone());
+
+                Task::future(async move {
+                    set_locked_hint(true).await;
+                    let res = tokio::task::spawn_blocking(move || {
+                        let service = CONFIG.lock.pam_service.as_deref().unwrap_or(PAM_SERVICE);
+                        session_lock::run(service, &current_user()?, unlock)
+                    })
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|res| res)
+                    .map_err(|err| format!("{err:#}"));
+                    set_locked_hint(false).await;
+                    res
+                })
+                .map(Message::Unlocked)
+            }
+            Message::Unlock => {
+                if let Some(unlock) = &self.unlock {
+                    unlock.store(true, Ordering::Relaxed);
+                }
+                Task::none()
+            }
+            Message::Unlocked(res) => {
+                self.unlock = None;
+                if let Err(err) = res {
+                    log_err!("Error locking session: {err}");
+                }
+                Task::none()
+            }
+        }
+    }
+}
+
+async fn set_locked_hint(locked: bool) {
+    let res = match logind::session().await {
+        Ok(session) => session.set_locked_hint(locked).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = res {
+        info!("Error setting locked hint: {err:?}");
+    }
+}
+
+/// the login name pam checks the password of
+fn current_user() -> anyhow::Result<String> {
+    let passwd = unsafe { libc::getpwuid(libc::getuid()) };
+    if passwd.is_null() {
+        anyhow::bail!("No passwd entry for the current user");
+    }
+
+    let name = unsafe { CStr::from_ptr((*passwd).pw_name) };
+    Ok(name.to_string_lossy().into_owned())
+}
@@ -0,0 +1,140 @@
+//! password checks through PAM, libpam is loaded at runtime so building
+//! doesn't need its headers
+use std::{
+    ffi::{CStr, CString, c_char, c_int, c_void},
+    ptr,
+};
+
+use anyhow::{anyhow, bail};
+use libloading::{Library, Symbol};
+
+use crate::lockscreen::password::Password;
+
+const PAM_SUCCESS: c_int = 0;
+const PAM_BUF_ERR: c_int = 5;
+const PAM_CONV_ERR: c_int = 19;
+const PAM_PROMPT_ECHO_OFF: c_int = 1;
+const PAM_PROMPT_ECHO_ON: c_int = 2;
+const PAM_REFRESH_CRED: c_int = 0x0010;
+
+#[repr(C)]
+struct PamHandle {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct PamMessage {
+    msg_style: c_int,
+    msg: *const c_char,
+}
+
+#[repr(C)]
+struct PamResponse {
+    resp: *mut c_char,
+    resp_retcode: c_int,
+}
+
+type ConvFn = unsafe extern "C" fn(
+    c_int,
+    *mut *const PamMessage,
+    *mut *mut PamResponse,
+    *mut c_void,
+) -> c_int;
+
+#[repr(C)]
+struct PamConv {
+    conv: ConvFn,
+    appdata_ptr: *mut c_void,
+}
+
+type StartFn = unsafe extern "C" fn(
+    *const c_char,
+    *const c_char,
+    *const PamConv,
+    *mut *mut PamHandle,
+) -> c_int;
+type HandleFn = unsafe extern "C" fn(*mut PamHandle, c_int) -> c_int;
+type StrErrorFn = unsafe extern "C" fn(*mut PamHandle, c_int) -> *const c_char;
+
+/// answers every prompt with the password, pam frees the responses
+unsafe extern "C" fn converse(
+    num_msg: c_int,
+    msg: *mut *const PamMessage,
+    resp: *mut *mut PamResponse,
+    appdata_ptr: *mut c_void,
+) -> c_int {
+    let Ok(len) = usize::try_from(num_msg) else {
+        return PAM_CONV_ERR;
+    };
+    let password = appdata_ptr as *const c_char;
+
+    unsafe {
+        let responses = libc::calloc(len, size_of::<PamResponse>()) as *mut PamResponse;
+        if responses.is_null() {
+            return PAM_BUF_ERR;
+        }
+
+        for idx in 0..len {
+            let message = &**msg.add(idx);
+            let response = &mut *responses.add(idx);
+            response.resp = match message.msg_style {
+                PAM_PROMPT_ECHO_OFF | PAM_PROMPT_ECHO_ON => libc::strdup(password),
+                // info and error messages need no answer
+                _ => ptr::null_mut(),
+            };
+        }
+
+        *resp = responses;
+    }
+    PAM_SUCCESS
+}
+
+/// check `password` for `user` against the pam `service`
+/// the password is borrowed, the only copy is the strdup answering pam, which
+/// pam frees itself
+pub fn authenticate(service: &str, user: &str, password: &Password) -> anyhow::Result<()> {
+    let service = CString::new(service)?;
+    let user = CString::new(user)?;
+    let password = password.as_c_str();
+
+    unsafe {
+        let lib = Library::new("libpam.so.0")?;
+        let pam_start: Symbol<StartFn> = lib.get(b"pam_start\0")?;
+        let pam_authenticate: Symbol<HandleFn> = lib.get(b"pam_authenticate\0")?;
+        let pam_acct_mgmt: Symbol<HandleFn> = lib.get(b"pam_acct_mgmt\0")?;
+        let pam_setcred: Symbol<HandleFn> = lib.get(b"pam_setcred\0")?;
+        let pam_end: Symbol<HandleFn> = lib.get(b"pam_end\0")?;
+        let pam_strerror: Symbol<StrErrorFn> = lib.get(b"pam_strerror\0")?;
+
+        let conv = PamConv {
+            conv: converse,
+            appdata_ptr: password.as_ptr() as *mut c_void,
+        };
+        let mut handle = ptr::null_mut();
+        let status = pam_start(service.as_ptr(), user.as_ptr(), &conv, &mut handle);
+        if status != PAM_SUCCESS {
+            bail!("pam_start failed with {status}");
+        }
+
+        let mut status = pam_authenticate(handle, 0);
+        if status == PAM_SUCCESS {
+            status = pam_acct_mgmt(handle, 0);
+        }
+        if status == PAM_SUCCESS {
+            // e.g. renews kerberos tickets, failing it shouldn't keep us locked
+            pam_setcred(handle, PAM_REFRESH_CRED);
+        }
+
+        let res = if status == PAM_SUCCESS {
+            Ok(())
+        } else {
+            let err = pam_strerror(handle, status);
+            Err(match err.is_null() {
+                true => anyhow!("pam error {status}"),
+                false => anyhow!("{}", CStr::from_ptr(err).to_string_lossy()),
+            })
+        };
+        pam_end(handle, status);
+        res
+    }
+}
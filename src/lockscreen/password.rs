@@ -0,0 +1,82 @@
+//! the typed password, kept in one fixed buffer that is never reallocated and
+//! zeroed when cleared or dropped, so no copies are left in freed memory
+use std::{
+    ffi::CStr,
+    ptr,
+    sync::atomic::{Ordering, compiler_fence},
+};
+
+/// bytes, including the nul terminator pam reads up to
+const CAPACITY: usize = 512;
+
+pub struct Password {
+    buf: Box<[u8; CAPACITY]>,
+    /// always below `CAPACITY`, so the buffer stays nul terminated
+    len: usize,
+}
+
+impl Password {
+    pub fn new() -> Self {
+        Self {
+            buf: Box::new([0; CAPACITY]),
+            len: 0,
+        }
+    }
+
+    /// ignored once the buffer is full
+    pub fn push(&mut self, ch: char) {
+        let width = ch.len_utf8();
+        if ch == '\0' || self.len + width >= CAPACITY {
+            return;
+        }
+        ch.encode_utf8(&mut self.buf[self.len..self.len + width]);
+        self.len += width;
+    }
+
+    pub fn pop(&mut self) {
+        let Some(start) = (0..self.len)
+            .rev()
+            .find(|idx| !is_continuation(self.buf[*idx]))
+        else {
+            return;
+        };
+        zero(&mut self.buf[start..self.len]);
+        self.len = start;
+    }
+
+    pub fn clear(&mut self) {
+        zero(&mut self.buf[..self.len]);
+        self.len = 0;
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.buf[..self.len]
+            .iter()
+            .filter(|byte| !is_continuation(**byte))
+            .count()
+    }
+
+    /// borrowed for pam without copying
+    pub fn as_c_str(&self) -> &CStr {
+        CStr::from_bytes_until_nul(&self.buf[..]).unwrap_or_default()
+    }
+}
+
+impl Drop for Password {
+    fn drop(&mut self) {
+        zero(&mut self.buf[..]);
+    }
+}
+
+/// a utf-8 byte that continues a char
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+/// volatile so the writes aren't optimized away before the buffer is freed
+fn zero(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
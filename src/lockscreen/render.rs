@@ -0,0 +1,214 @@
+//! software rendering of the lock screen, lock surfaces aren't iced windows
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use tiny_skia::{FillRule, Paint, Path, PathBuilder, Pixmap, Stroke, Transform};
+
+use crate::{fira_fonts, theme::CAT_THEME};
+
+/// dots shown for the password, longer ones don't widen the field
+const MAX_DOTS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthState {
+    Idle,
+    Checking,
+    Failed(String),
+}
+
+/// what's shown on every output
+pub struct LockView<'a> {
+    pub time: String,
+    pub date: String,
+    pub user: &'a str,
+    pub password_len: usize,
+    pub auth: &'a AuthState,
+}
+
+pub struct Painter {
+    regular: FontRef<'static>,
+    bold: FontRef<'static>,
+}
+
+impl Painter {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            regular: FontRef::try_from_slice(fira_fonts::REGULAR_BYTES)?,
+            bold: FontRef::try_from_slice(fira_fonts::BOLD_BYTES)?,
+        })
+    }
+
+    /// draw the view centered, sizes are multiplied by `scale`
+    pub fn draw(&self, pixmap: &mut Pixmap, view: &LockView, scale: f32) {
+        let theme = &CAT_THEME;
+        let px = |size: f32| size * scale;
+        let center_x = pixmap.width() as f32 / 2.0;
+        let center_y = pixmap.height() as f32 / 2.0;
+
+        pixmap.fill(skia_color(theme.base()));
+
+        let clock_y = center_y - px(60.0);
+        self.text(
+            pixmap,
+            &self.bold,
+            px(96.0),
+            &view.time,
+            center_x,
+            clock_y,
+            theme.text_color(),
+        );
+        self.text(
+            pixmap,
+            &self.regular,
+            px(24.0),
+            &view.date,
+            center_x,
+            clock_y + px(40.0),
+            theme.subtext0(),
+        );
+
+        let user_y = center_y + px(50.0);
+        self.text(
+            pixmap,
+            &self.regular,
+            px(20.0),
+            view.user,
+            center_x,
+            user_y,
+            theme.lavender(),
+        );
+
+        // password field
+        let (field_w, field_h) = (px(320.0), px(48.0));
+        let field_x = center_x - field_w / 2.0;
+        let field_y = user_y + px(16.0);
+        let border = match view.auth {
+            AuthState::Idle => theme.lavender(),
+            AuthState::Checking => theme.yellow(),
+            AuthState::Failed(_) => theme.red(),
+        };
+        if let Some(path) = rounded_rect(field_x, field_y, field_w, field_h, px(8.0)) {
+            let mut paint = Paint::default();
+            paint.set_color(skia_color(theme.surface0()));
+            pixmap.fill_path(
+                &path,
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+
+            paint.set_color(skia_color(border));
+            let stroke = Stroke {
+                width: px(2.0),
+                ..Default::default()
+            };
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+
+        let dots = view.password_len.min(MAX_DOTS);
+        let dot_gap = px(16.0);
+        let first_x = center_x - dot_gap * (dots as f32 - 1.0) / 2.0;
+        let mut paint = Paint::default();
+        paint.set_color(skia_color(theme.text_color()));
+        (0..dots)
+            .filter_map(|idx| {
+                PathBuilder::from_circle(
+                    first_x + dot_gap * idx as f32,
+                    field_y + field_h / 2.0,
+                    px(5.0),
+                )
+            })
+            .for_each(|dot| {
+                pixmap.fill_path(&dot, &paint, FillRule::Winding, Transform::identity(), None);
+            });
+
+        let status = match view.auth {
+            AuthState::Idle => None,
+            AuthState::Checking => Some(("Checking…".to_owned(), theme.yellow())),
+            AuthState::Failed(err) => Some((err.clone(), theme.red())),
+        };
+        if let Some((status, color)) = status {
+            let status_y = field_y + field_h + px(32.0);
+            self.text(
+                pixmap,
+                &self.regular,
+                px(16.0),
+                &status,
+                center_x,
+                status_y,
+                color,
+            );
+        }
+    }
+
+    /// draw a line of text centered on `center_x` with its baseline at `baseline`
+    #[allow(clippy::too_many_arguments)]
+    fn text(
+        &self,
+        pixmap: &mut Pixmap,
+        font: &FontRef<'static>,
+        size: f32,
+        text: &str,
+        center_x: f32,
+        baseline: f32,
+        color: iced::Color,
+    ) {
+        let scaled = font.as_scaled(PxScale::from(size));
+        let width: f32 = text
+            .chars()
+            .map(|c| scaled.h_advance(scaled.glyph_id(c)))
+            .sum();
+
+        let (pix_w, pix_h) = (pixmap.width() as i32, pixmap.height() as i32);
+        let data = pixmap.data_mut();
+        let mut x = center_x - width / 2.0;
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            let glyph = id.with_scale_and_position(size, point(x, baseline));
+            x += scaled.h_advance(id);
+
+            let Some(outline) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                if px < 0 || py < 0 || px >= pix_w || py >= pix_h {
+                    return;
+                }
+                let idx = (py * pix_w + px) as usize * 4;
+                blend(&mut data[idx..idx + 4], color, coverage);
+            });
+        }
+    }
+}
+
+/// blend `color` over a premultiplied rgba pixel
+fn blend(pixel: &mut [u8], color: iced::Color, coverage: f32) {
+    let alpha = (color.a * coverage).clamp(0.0, 1.0);
+    let src = [color.r, color.g, color.b];
+    for (channel, src) in pixel.iter_mut().zip(src) {
+        *channel = (src * alpha * 255.0 + *channel as f32 * (1.0 - alpha)).round() as u8;
+    }
+    pixel[3] = (alpha * 255.0 + pixel[3] as f32 * (1.0 - alpha)).round() as u8;
+}
+
+fn rounded_rect(x: f32, y: f32, w: f32, h: f32, r: f32) -> Option<Path> {
+    let mut pb = PathBuilder::new();
+    pb.move_to(x + r, y);
+    pb.line_to(x + w - r, y);
+    pb.quad_to(x + w, y, x + w, y + r);
+    pb.line_to(x + w, y + h - r);
+    pb.quad_to(x + w, y + h, x + w - r, y + h);
+    pb.line_to(x + r, y + h);
+    pb.quad_to(x, y + h, x, y + h - r);
+    pb.line_to(x, y + r);
+    pb.quad_to(x, y, x + r, y);
+    pb.close();
+    pb.finish()
+}
+
+fn skia_color(color: iced::Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba(color.r, color.g, color.b, color.a)
+        .unwrap_or(tiny_skia::Color::BLACK)
+}
@@ -0,0 +1,514 @@
+//! the lock itself through ext-session-lock-v1, the compositor hides
+//! everything else, layer shell windows included, until we unlock
+use std::{
+    fs::File,
+    io,
+    os::{
+        fd::{AsFd, AsRawFd, FromRawFd, OwnedFd},
+        unix::fs::FileExt,
+    },
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use anyhow::{Context, bail};
+use time::{OffsetDateTime, format_description::BorrowedFormatItem};
+use time_macros::format_description;
+use tiny_skia::Pixmap;
+use tracing::info;
+use wayland_client::{
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum,
+    backend::WaylandError,
+    delegate_noop,
+    globals::{GlobalListContents, registry_queue_init},
+    protocol::{
+        wl_buffer::WlBuffer,
+        wl_compositor::WlCompositor,
+        wl_keyboard::{self, WlKeyboard},
+        wl_output::{self, WlOutput},
+        wl_registry::{self, WlRegistry},
+        wl_seat::{self, WlSeat},
+        wl_shm::{self, WlShm},
+        wl_shm_pool::WlShmPool,
+        wl_surface::WlSurface,
+    },
+};
+use wayland_protocols::ext::session_lock::v1::client::{
+    ext_session_lock_manager_v1::ExtSessionLockManagerV1,
+    ext_session_lock_surface_v1::{self, ExtSessionLockSurfaceV1},
+    ext_session_lock_v1::{self, ExtSessionLockV1},
+};
+
+use crate::lockscreen::{
+    keyboard::{KeyInput, Keyboard},
+    pam,
+    password::Password,
+    render::{AuthState, LockView, Painter},
+};
+
+const TIME_FORMAT: &[BorrowedFormatItem] = format_description!("[hour]:[minute]");
+const DATE_FORMAT: &[BorrowedFormatItem] =
+    format_description!("[weekday], [month repr:long] [day padding:none]");
+/// how often the clock and the unlock flag are checked
+const POLL_MS: i32 = 500;
+
+/// two buffers in one pool, drawn into in turns
+struct Buffers {
+    file: File,
+    buffers: [WlBuffer; 2],
+    next: usize,
+    /// in buffer pixels
+    size: (u32, u32),
+}
+
+impl Buffers {
+    fn new(shm: &WlShm, size: (u32, u32), qh: &QueueHandle<State>) -> anyhow::Result<Self> {
+        let (width, height) = size;
+        let stride = width * 4;
+        let len = stride * height;
+
+        let fd = unsafe { libc::memfd_create(c"icedshell-lock".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let file = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+        file.set_len(len as u64 * 2)?;
+
+        let pool = shm.create_pool(file.as_fd(), (len * 2) as i32, qh, ());
+        let buffers = [0, 1].map(|idx| {
+            pool.create_buffer(
+                (len * idx) as i32,
+                width as i32,
+                height as i32,
+                stride as i32,
+                wl_shm::Format::Argb8888,
+                qh,
+                (),
+            )
+        });
+        pool.destroy();
+
+        Ok(Self {
+            file,
+            buffers,
+            next: 0,
+            size,
+        })
+    }
+
+    /// copy the pixmap into the next buffer
+    fn write(&mut self, pixmap: &Pixmap) -> anyhow::Result<&WlBuffer> {
+        let idx = self.next;
+        self.next = (self.next + 1) % 2;
+
+        // premultiplied rgba to little endian argb
+        let data: Vec<u8> = pixmap
+            .data()
+            .chunks_exact(4)
+            .flat_map(|px| [px[2], px[1], px[0], px[3]])
+            .collect();
+        self.file.write_all_at(&data, (data.len() * idx) as u64)?;
+
+        Ok(&self.buffers[idx])
+    }
+}
+
+impl Drop for Buffers {
+    fn drop(&mut self) {
+        self.buffers.iter().for_each(WlBuffer::destroy);
+    }
+}
+
+struct LockSurface {
+    surface: WlSurface,
+    lock_surface: ExtSessionLockSurfaceV1,
+    /// in surface coords, None until configured
+    size: Option<(u32, u32)>,
+    buffers: Option<Buffers>,
+}
+
+impl Drop for LockSurface {
+    fn drop(&mut self) {
+        self.lock_surface.destroy();
+        self.surface.destroy();
+    }
+}
+
+struct Output {
+    /// registry name
+    name: u32,
+    output: WlOutput,
+    scale: i32,
+    surface: LockSurface,
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        if self.output.version() >= 3 {
+            self.output.release();
+        }
+    }
+}
+
+struct State {
+    compositor: WlCompositor,
+    shm: WlShm,
+    lock: ExtSessionLockV1,
+    outputs: Vec<Output>,
+    keyboard: Keyboard,
+    is_locked: bool,
+    /// the compositor ended the lock, or refused it
+    finished: bool,
+    password: Password,
+    auth: AuthState,
+    submit: bool,
+    /// redraw on the next loop
+    dirty: bool,
+}
+
+impl State {
+    fn add_output(&mut self, name: u32, output: WlOutput, qh: &QueueHandle<Self>) {
+        let surface = self.compositor.create_surface(qh, ());
+        let lock_surface = self.lock.get_lock_surface(&surface, &output, qh, name);
+
+        self.outputs.push(Output {
+            name,
+            output,
+            scale: 1,
+            surface: LockSurface {
+                surface,
+                lock_surface,
+                size: None,
+                buffers: None,
+            },
+        });
+    }
+
+    fn draw(
+        &mut self,
+        painter: &Painter,
+        user: &str,
+        qh: &QueueHandle<Self>,
+    ) -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let view = LockView {
+            time: now.format(TIME_FORMAT).unwrap_or_default(),
+            date: now.format(DATE_FORMAT).unwrap_or_default(),
+            user,
+            password_len: self.password.char_count(),
+            auth: &self.auth,
+        };
+
+        for output in self.outputs.iter_mut() {
+            let lock = &mut output.surface;
+            let Some((width, height)) = lock.size else {
+                continue;
+            };
+            let scale = output.scale.max(1);
+            let size = (width * scale as u32, height * scale as u32);
+
+            let mut pixmap = Pixmap::new(size.0, size.1).context("Empty lock surface")?;
+            painter.draw(&mut pixmap, &view, scale as f32);
+
+            if lock
+                .buffers
+                .as_ref()
+                .is_none_or(|buffers| buffers.size != size)
+            {
+                lock.buffers = Some(Buffers::new(&self.shm, size, qh)?);
+            }
+            let Some(buffers) = lock.buffers.as_mut() else {
+                continue;
+            };
+            let buffer = buffers.write(&pixmap)?;
+
+            lock.surface.set_buffer_scale(scale);
+            lock.surface.attach(Some(buffer), 0, 0);
+            lock.surface
+                .damage_buffer(0, 0, size.0 as i32, size.1 as i32);
+            lock.surface.commit();
+        }
+
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// wait up to `timeout_ms` for events and dispatch them
+fn dispatch_timeout(
+    queue: &mut EventQueue<State>,
+    state: &mut State,
+    timeout_ms: i32,
+) -> anyhow::Result<()> {
+    queue.flush()?;
+    queue.dispatch_pending(state)?;
+
+    if let Some(guard) = queue.prepare_read() {
+        let mut fds = [libc::pollfd {
+            fd: guard.connection_fd().as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        let ready = unsafe { libc::poll(fds.as_mut_ptr(), 1, timeout_ms) };
+        if ready > 0 {
+            match guard.read() {
+                Ok(_) => (),
+                Err(WaylandError::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => (),
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    queue.dispatch_pending(state)?;
+    Ok(())
+}
+
+/// lock the session and block until the password is entered or `unlock` is set
+pub fn run(service: &str, user: &str, unlock: Arc<AtomicBool>) -> anyhow::Result<()> {
+    let painter = Painter::new()?;
+    let conn = Connection::connect_to_env()?;
+    let (globals, mut queue) = registry_queue_init::<State>(&conn)?;
+    let qh = queue.handle();
+
+    let compositor: WlCompositor = globals.bind(&qh, 4..=6, ())?;
+    let shm: WlShm = globals.bind(&qh, 1..=1, ())?;
+    let _seat: WlSeat = globals.bind(&qh, 1..=7, ())?;
+    let manager: ExtSessionLockManagerV1 = globals
+        .bind(&qh, 1..=1, ())
+        .context("Compositor doesn't support ext-session-lock-v1")?;
+    let lock = manager.lock(&qh, ());
+
+    let mut state = State {
+        compositor,
+        shm,
+        lock,
+        outputs: Vec::new(),
+        keyboard: Keyboard::new()?,
+        is_locked: false,
+        finished: false,
+        password: Password::new(),
+        auth: AuthState::Idle,
+        submit: false,
+        dirty: false,
+    };
+
+    let outputs = globals.contents().with_list(|list| {
+        list.iter()
+            .filter(|global| global.interface == WlOutput::interface().name)
+            .map(|global| (global.name, global.version))
+            .collect::<Vec<_>>()
+    });
+    for (name, version) in outputs {
+        let output: WlOutput = globals.registry().bind(name, version.min(4), &qh, name);
+        state.add_output(name, output, &qh);
+    }
+
+    let mut last_time = String::new();
+    loop {
+        dispatch_timeout(&mut queue, &mut state, POLL_MS)?;
+
+        if state.finished {
+            if state.is_locked {
+                bail!("Compositor ended the lock");
+            }
+            bail!("Compositor refused the lock, is another locker running?");
+        }
+        if unlock.load(Ordering::Relaxed) {
+            info!("Unlocked by logind");
+            break;
+        }
+
+        if state.submit {
+            state.submit = false;
+            state.auth = AuthState::Checking;
+            state.draw(&painter, user, &qh)?;
+            queue.flush()?;
+
+            match pam::authenticate(service, user, &state.password) {
+                Ok(()) => break,
+                Err(err) => {
+                    info!("Error authenticating: {err:?}");
+                    state.auth = AuthState::Failed(err.to_string());
+                    state.password.clear();
+                    state.dirty = true;
+                }
+            }
+        }
+
+        let time = OffsetDateTime::now_local()
+            .ok()
+            .and_then(|now| now.format(TIME_FORMAT).ok())
+            .unwrap_or_default();
+        if state.dirty || time != last_time {
+            last_time = time;
+            state.draw(&painter, user, &qh)?;
+        }
+    }
+
+    state.password.clear();
+    state.lock.unlock_and_destroy();
+    state.outputs.clear();
+    conn.roundtrip()?;
+    Ok(())
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        state: &mut Self,
+        registry: &WlRegistry,
+        event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } if interface == WlOutput::interface().name => {
+                let output = registry.bind(name, version.min(4), qh, name);
+                state.add_output(name, output, qh);
+            }
+            wl_registry::Event::GlobalRemove { name } => {
+                state.outputs.retain(|output| output.name != name);
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<WlOutput, u32> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &WlOutput,
+        event: wl_output::Event,
+        name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Scale { factor } = event
+            && let Some(output) = state.outputs.iter_mut().find(|output| output.name == *name)
+        {
+            output.scale = factor;
+            state.dirty = true;
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for State {
+    fn event(
+        _state: &mut Self,
+        seat: &WlSeat,
+        event: wl_seat::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+            && capabilities.contains(wl_seat::Capability::Keyboard)
+        {
+            seat.get_keyboard(qh, ());
+        }
+    }
+}
+
+impl Dispatch<WlKeyboard, ()> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &WlKeyboard,
+        event: wl_keyboard::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_keyboard::Event::Keymap { fd, size, .. } => {
+                if let Err(err) = state.keyboard.set_keymap(fd, size) {
+                    info!("Error loading keymap: {err:?}");
+                }
+            }
+            wl_keyboard::Event::Modifiers {
+                mods_depressed,
+                mods_latched,
+                mods_locked,
+                group,
+                ..
+            } => state
+                .keyboard
+                .update_modifiers(mods_depressed, mods_latched, mods_locked, group),
+            wl_keyboard::Event::Key {
+                key,
+                state: WEnum::Value(wl_keyboard::KeyState::Pressed),
+                ..
+            } => {
+                match state.keyboard.press(key) {
+                    KeyInput::Char(ch) => state.password.push(ch),
+                    KeyInput::Backspace => {
+                        state.password.pop();
+                    }
+                    KeyInput::Clear => state.password.clear(),
+                    KeyInput::Submit => state.submit = true,
+                    KeyInput::None => return,
+                }
+                if state.auth != AuthState::Checking {
+                    state.auth = AuthState::Idle;
+                }
+                state.dirty = true;
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ExtSessionLockV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _proxy: &ExtSessionLockV1,
+        event: ext_session_lock_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_session_lock_v1::Event::Locked => state.is_locked = true,
+            ext_session_lock_v1::Event::Finished => state.finished = true,
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ExtSessionLockSurfaceV1, u32> for State {
+    fn event(
+        state: &mut Self,
+        lock_surface: &ExtSessionLockSurfaceV1,
+        event: ext_session_lock_surface_v1::Event,
+        name: &u32,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let ext_session_lock_surface_v1::Event::Configure {
+            serial,
+            width,
+            height,
+        } = event
+        {
+            lock_surface.ack_configure(serial);
+            if let Some(output) = state.outputs.iter_mut().find(|output| output.name == *name) {
+                output.surface.size = Some((width, height));
+                state.dirty = true;
+            }
+        }
+    }
+}
+
+delegate_noop!(State: ignore WlCompositor);
+delegate_noop!(State: ignore WlSurface);
+delegate_noop!(State: ignore WlShm);
+delegate_noop!(State: ignore WlShmPool);
+delegate_noop!(State: ignore WlBuffer);
+delegate_noop!(State: ExtSessionLockManagerV1);
@@ -0,0 +1,79 @@
+use zbus::{proxy, zvariant::OwnedObjectPath};
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+pub trait Manager {
+    fn suspend(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn hibernate(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn suspend_then_hibernate(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn reboot(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn power_off(&self, interactive: bool) -> zbus::Result<()>;
+
+    fn set_reboot_to_firmware_setup(&self, enable: bool) -> zbus::Result<()>;
+
+    /// "yes", "no", "challenge" or "na"
+    fn can_suspend(&self) -> zbus::Result<String>;
+
+    fn can_hibernate(&self) -> zbus::Result<String>;
+
+    fn can_suspend_then_hibernate(&self) -> zbus::Result<String>;
+
+    fn can_reboot(&self) -> zbus::Result<String>;
+
+    fn can_power_off(&self) -> zbus::Result<String>;
+
+    fn can_reboot_to_firmware_setup(&self) -> zbus::Result<String>;
+
+    /// `usec` is the realtime clock in microseconds
+    fn schedule_shutdown(&self, kind: &str, usec: u64) -> zbus::Result<()>;
+
+    fn cancel_scheduled_shutdown(&self) -> zbus::Result<bool>;
+
+    /// (what, who, why, mode, uid, pid)
+    fn list_inhibitors(&self) -> zbus::Result<Vec<(String, String, String, String, u32, u32)>>;
+
+    fn get_session(&self, session_id: &str) -> zbus::Result<OwnedObjectPath>;
+
+    /// `start` is false once the system resumed
+    #[zbus(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+
+    /// colon separated, e.g. "sleep:idle"
+    #[zbus(property)]
+    fn block_inhibited(&self) -> zbus::Result<String>;
+
+    /// (kind, usec), usec is 0 when nothing is scheduled
+    #[zbus(property)]
+    fn scheduled_shutdown(&self) -> zbus::Result<(String, u64)>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1/session/auto"
+)]
+pub trait Session {
+    fn lock(&self) -> zbus::Result<()>;
+
+    /// tell logind whether a locker is shown
+    fn set_locked_hint(&self, locked: bool) -> zbus::Result<()>;
+
+    /// subsystem is "backlight" or "leds", name is the sysfs device
+    fn set_brightness(&self, subsystem: &str, name: &str, brightness: u32) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "Lock")]
+    fn lock_requested(&self) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "Unlock")]
+    fn unlock_requested(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+}
@@ -0,0 +1,191 @@
+//! power and session management through systemd-logind
+mod dbus;
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use iced::futures::{Stream, StreamExt, stream};
+use tokio::sync::OnceCell;
+use tracing::error as log_err;
+use zbus::{Connection, proxy::CacheProperties};
+
+pub use dbus::{ManagerProxy, SessionProxy};
+
+/// one system bus connection shared by every caller
+static CONN: OnceCell<Connection> = OnceCell::const_new();
+
+async fn conn() -> zbus::Result<&'static Connection> {
+    CONN.get_or_try_init(Connection::system).await
+}
+
+/// properties are read fresh, logind doesn't signal changes to most of them
+pub async fn manager() -> zbus::Result<ManagerProxy<'static>> {
+    ManagerProxy::builder(conn().await?)
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+}
+
+/// the session the shell runs in
+pub async fn session() -> zbus::Result<SessionProxy<'static>> {
+    SessionProxy::builder(conn().await?)
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+}
+
+/// the session by its real path, signals aren't sent on `session/auto`
+async fn own_session() -> zbus::Result<SessionProxy<'static>> {
+    let id = session().await?.id().await?;
+    let path = manager().await?.get_session(&id).await?;
+
+    SessionProxy::builder(conn().await?)
+        .path(path)?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+}
+
+/// asked by logind, e.g. through `loginctl lock-session`
+#[derive(Debug, Clone, Copy)]
+pub enum LockSignal {
+    Lock,
+    Unlock,
+}
+
+const RETRY_MIN: Duration = Duration::from_secs(1);
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// set while the lock signals are received, nothing locks otherwise
+static IS_LOCK_WATCHED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_lock_watched() -> bool {
+    IS_LOCK_WATCHED.load(Ordering::Relaxed)
+}
+
+async fn lock_signals() -> zbus::Result<impl Stream<Item = LockSignal> + use<>> {
+    let session = own_session().await?;
+    let lock = session.receive_lock_requested().await?;
+    let unlock = session.receive_unlock_requested().await?;
+
+    Ok(stream::select(
+        lock.map(|_| LockSignal::Lock),
+        unlock.map(|_| LockSignal::Unlock),
+    ))
+}
+
+/// resubscribes with a backoff, e.g. when there's no session yet at startup
+pub fn watch_lock() -> impl Stream<Item = LockSignal> {
+    stream::unfold(None, |retry_in: Option<Duration>| async move {
+        if let Some(retry_in) = retry_in {
+            tokio::time::sleep(retry_in).await;
+        }
+
+        match lock_signals().await {
+            Ok(signals) => {
+                IS_LOCK_WATCHED.store(true, Ordering::Relaxed);
+                let signals = signals.map(Some).chain(stream::once(async {
+                    IS_LOCK_WATCHED.store(false, Ordering::Relaxed);
+                    log_err!("Lock signals ended, resubscribing");
+                    None
+                }));
+                // resubscribe soon should the signals end
+                Some((signals.boxed(), Some(RETRY_MIN)))
+            }
+            Err(err) => {
+                IS_LOCK_WATCHED.store(false, Ordering::Relaxed);
+                let retry_in = retry_in.map_or(RETRY_MIN, |retry_in| (retry_in * 2).min(RETRY_MAX));
+                log_err!("Error watching lock signals, retrying in {retry_in:?}: {err:?}");
+                Some((stream::empty().boxed(), Some(retry_in)))
+            }
+        }
+    })
+    .flatten()
+    .filter_map(|signal| async move { signal })
+}
+
+/// emits once the system resumed from sleep
+pub fn watch_resume() -> impl Stream<Item = ()> {
+    stream::once(async {
+        let signals = manager().await?.receive_prepare_for_sleep().await?;
+        zbus::Result::Ok(signals.filter_map(|signal| async move {
+            let is_resumed = signal.args().is_ok_and(|args| !args.start);
+            is_resumed.then_some(())
+        }))
+    })
+    .filter_map(|res| async move {
+        res.inspect_err(|err| log_err!("Error watching resume: {err:?}"))
+            .ok()
+    })
+    .flatten()
+}
+
+/// a lock taken to delay or block sleep, shutdown or idle
+#[derive(Debug, Clone)]
+pub struct Inhibitor {
+    /// colon separated, e.g. "shutdown:sleep"
+    pub what: String,
+    pub who: String,
+    pub why: String,
+    /// "block" or "delay"
+    pub mode: String,
+}
+
+impl Inhibitor {
+    /// whether it blocks suspending or shutting down, delay inhibitors
+    /// (e.g. NetworkManager's) only hold it back for a moment
+    pub fn blocks_power(&self) -> bool {
+        self.mode == "block"
+            && self
+                .what
+                .split(':')
+                .any(|what| what == "shutdown" || what == "sleep")
+    }
+}
+
+pub async fn list_inhibitors() -> zbus::Result<Vec<Inhibitor>> {
+    let inhibitors = manager().await?.list_inhibitors().await?;
+
+    Ok(inhibitors
+        .into_iter()
+        .map(|(what, who, why, mode, _uid, _pid)| Inhibitor {
+            what,
+            who,
+            why,
+            mode,
+        })
+        .collect())
+}
+
+/// whether something, e.g. a video call, took a blocking idle inhibitor
+pub async fn is_idle_inhibited() -> zbus::Result<bool> {
+    let inhibited = manager().await?.block_inhibited().await?;
+
+    Ok(inhibited.split(':').any(|what| what == "idle"))
+}
+
+/// when the scheduled shutdown happens, if any
+pub async fn scheduled_shutdown() -> zbus::Result<Option<SystemTime>> {
+    let (_kind, usec) = manager().await?.scheduled_shutdown().await?;
+
+    Ok((usec != 0).then(|| UNIX_EPOCH + Duration::from_micros(usec)))
+}
+
+/// poll the scheduled shutdown, logind doesn't signal changes to it
+pub fn watch_scheduled_shutdown() -> impl Stream<Item = Option<SystemTime>> {
+    stream::unfold(true, |is_first| async move {
+        if !is_first {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+        let scheduled = scheduled_shutdown().await.unwrap_or_default();
+
+        Some((scheduled, false))
+    })
+}
+
+/// whether a `Can*` answer allows the action, "challenge" asks for auth
+pub fn is_allowed(answer: &str) -> bool {
+    matches!(answer, "yes" | "challenge")
+}
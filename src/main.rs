@@ -1,11 +1,16 @@
 mod audio;
 mod bars;
+mod clipboard;
 mod cmd;
+mod config;
 mod daemon;
 mod datetime;
 mod feature;
 mod fira_fonts;
+mod idle;
 mod launcher;
+mod lockscreen;
+mod logind;
 mod niri;
 mod osd;
 mod powermenu;
@@ -18,6 +23,7 @@ mod widget;
 
 use crate::{
     daemon::{Init, start},
+    launcher::LauncherArgs,
     osd::OsdArgs,
     powermenu::PowerArgs,
 };
@@ -40,9 +46,12 @@ struct Cli {
 #[derive(Subcommand, Debug, Display, Clone)]
 enum AppCommand {
     Daemon,
-    Launcher,
+    Launcher(LauncherArgs),
     Osd(OsdArgs),
     PowerMenu(PowerArgs),
+    Lock,
+    /// toggle pausing the idle timeouts
+    Caffeine,
     // Notify
 }
 
@@ -88,8 +97,8 @@ fn main() -> anyhow::Result<()> {
             )?;
             Ok(())
         }
-        AppCommand::Launcher => {
-            match socket::send_launcher_req() {
+        AppCommand::Launcher(args) => {
+            match socket::send_launcher_req(args) {
                 Ok(res) => info!("Res: {res:?}"),
                 Err(err) => log_err!("request err: {err:?}"),
             };
@@ -110,5 +119,19 @@ fn main() -> anyhow::Result<()> {
             };
             Ok(())
         }
+        AppCommand::Lock => {
+            match socket::send_lock_req() {
+                Ok(res) => info!("Res: {res:?}"),
+                Err(err) => log_err!("request err: {err:?}"),
+            };
+            Ok(())
+        }
+        AppCommand::Caffeine => {
+            match socket::send_caffeine_req() {
+                Ok(res) => info!("Res: {res:?}"),
+                Err(err) => log_err!("request err: {err:?}"),
+            };
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,28 @@
+use niri_ipc::{Action, Reply, Request, Response};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+};
+
+use crate::niri::stream::{NiriStream, NiriStreamError};
+
+/// send a single action to niri over a new connection
+pub async fn send(action: Action) -> Result<(), NiriStreamError> {
+    let path = NiriStream::path()?;
+    let req_buff = serde_json::to_string(&Request::Action(action))? + "\n";
+
+    let mut stream = UnixStream::connect(path).await?;
+
+    stream.writable().await?;
+    stream.write_all(req_buff.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).await?;
+
+    let reply: Reply = serde_json::from_str(&line)?;
+    match reply.map_err(NiriStreamError::NiriActionRefused)? {
+        Response::Handled => Ok(()),
+        res => Err(NiriStreamError::NiriActionRefused(format!("{res:?}"))),
+    }
+}
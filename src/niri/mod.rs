@@ -1,5 +1,6 @@
+pub mod action;
 pub mod monitors;
 pub mod state_serv;
-mod stream;
+pub mod stream;
 pub mod win_comp;
 pub mod ws_comp;
@@ -61,6 +61,12 @@ struct WsMap(BTreeMap<WorkspaceId, Workspace>);
 #[derive(Debug, Clone, PartialEq, Eq, Hash, From)]
 pub struct WinId(u64);
 
+impl WinId {
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, From, PartialOrd, Ord, Default, Display)]
 pub struct WinIdx(usize);
 
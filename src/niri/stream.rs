@@ -22,6 +22,9 @@ pub enum NiriStreamError {
     #[error("Niri refused event stream")]
     NiriStreamRefused(String),
 
+    #[error("Niri refused action")]
+    NiriActionRefused(String),
+
     #[error("Serde failed to parse")]
     SerdeErr(String),
 }
@@ -38,13 +41,13 @@ impl From<serde_json::Error> for NiriStreamError {
     }
 }
 
-enum NiriStream {
+pub(super) enum NiriStream {
     Disconnected { attempts: u32 },
     Connected(BufReader<UnixStream>),
 }
 
 impl NiriStream {
-    fn path() -> Result<OsString, NiriStreamError> {
+    pub(super) fn path() -> Result<OsString, NiriStreamError> {
         var_os(socket::SOCKET_PATH_ENV).ok_or(NiriStreamError::NiriNoSocket)
     }
 
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     feature::{Comp, Feature},
+    system_info::battery_serv::Warning,
     theme::CAT_THEME,
     types::MonitorId,
 };
@@ -17,7 +18,7 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct Init {
     pub monitor: Option<MonitorId>,
-    pub command: OsdCommand,
+    pub modi: Modi,
 }
 
 #[derive(Debug, Clone, Display, Subcommand, Serialize, Deserialize)]
@@ -37,6 +38,18 @@ pub enum BrightLevel {
 pub enum Modi {
     Volume(VolumeLevel, Option<usize>),
     Brightness(BrightLevel, Option<usize>),
+    Battery(Warning, f64),
+    /// the session didn't lock, see the logs for why
+    LockFailed,
+}
+
+impl From<OsdCommand> for Modi {
+    fn from(command: OsdCommand) -> Self {
+        match command {
+            OsdCommand::Volume(VolArgs { command, val }) => Self::Volume(command, val),
+            OsdCommand::Bright(BrightArgs { command, val }) => Self::Brightness(command, val),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -57,19 +70,20 @@ impl Comp for Osd {
         input: Self::Init,
         f: impl Fn(Self::Message) -> O + iced::advanced::graphics::futures::MaybeSend + 'static,
     ) -> (Self, iced::Task<O>) {
-        let modi = match input.command {
-            OsdCommand::Volume(VolArgs { command, val }) => Modi::Volume(command, val),
-            OsdCommand::Bright(BrightArgs { command, val }) => Modi::Brightness(command, val),
+        // warnings stay up long enough to be read
+        let delay = match input.modi {
+            Modi::Battery(..) | Modi::LockFailed => 4000,
+            _ => 650,
         };
         let timeout = Task::perform(
-            tokio::time::sleep(tokio::time::Duration::from_millis(650)),
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay)),
             |_| Message::Timeout,
         )
         .map(f);
         (
             Self {
                 monitor: input.monitor,
-                modi,
+                modi: input.modi,
             },
             timeout,
         )
@@ -91,16 +105,28 @@ impl Comp for Osd {
 
             Modi::Brightness(BrightLevel::Inc, _) => fa_icon_solid("lightbulb"),
             Modi::Brightness(BrightLevel::Dec, _) => fa_icon("lightbulb"),
+
+            Modi::Battery(Warning::Low, _) => fa_icon_solid("battery-quarter"),
+            Modi::Battery(Warning::Critical, _) => fa_icon_solid("battery-empty"),
+            Modi::Battery(Warning::Suspend, _) => fa_icon_solid("moon"),
+
+            Modi::LockFailed => fa_icon_solid("lock-open"),
         };
         let val = match self.modi {
-            Modi::Volume(_, val) => val,
-            Modi::Brightness(_, val) => val,
-        }
-        .map(|val| val.clamp(0, 100));
+            Modi::Volume(_, val) => val.map(|val| val.clamp(0, 100).to_string()),
+            Modi::Brightness(_, val) => val.map(|val| val.clamp(0, 100).to_string()),
+            Modi::Battery(_, cap) => Some(format!("{cap:.0}")),
+            Modi::LockFailed => Some("failed".to_owned()),
+        };
+        let color = match self.modi {
+            Modi::Battery(Warning::Low, _) => theme.yellow(),
+            Modi::Battery(..) | Modi::LockFailed => theme.red(),
+            _ => theme.subtext0(),
+        };
 
-        let val = val.map(|val| text!("{}", val).size(spacing.xl3()).color(theme.subtext0()));
+        let val = val.map(|val| text!("{}", val).size(spacing.xl3()).color(color));
 
-        let icon = icon.size(spacing.xl3()).color(theme.subtext0());
+        let icon = icon.size(spacing.xl3()).color(color);
 
         let txt = match (icon, val) {
             (icon, Some(val)) => row!(icon, val).spacing(spacing.md()),
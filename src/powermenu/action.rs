@@ -0,0 +1,167 @@
+//! what the powermenu buttons do, set in the config
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::logind;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PowerCommand {
+    Lock,
+    Suspend,
+    Hibernate,
+    SuspendThenHibernate,
+    Reboot,
+    RebootToFirmware,
+    Poweroff,
+    /// power off in this many minutes
+    ScheduledPoweroff(u64),
+    CancelShutdown,
+    Logout,
+    /// any other command, as argv
+    Exec(Vec<String>),
+}
+
+impl PowerCommand {
+    /// whether logind allows it, commands are always available
+    pub async fn is_available(&self) -> anyhow::Result<bool> {
+        let manager = logind::manager().await?;
+        let answer = match self {
+            Self::Suspend => manager.can_suspend().await?,
+            Self::Hibernate => manager.can_hibernate().await?,
+            Self::SuspendThenHibernate => manager.can_suspend_then_hibernate().await?,
+            Self::Reboot => manager.can_reboot().await?,
+            Self::RebootToFirmware => manager.can_reboot_to_firmware_setup().await?,
+            Self::Poweroff | Self::ScheduledPoweroff(_) => manager.can_power_off().await?,
+            Self::CancelShutdown => {
+                return Ok(logind::scheduled_shutdown().await?.is_some());
+            }
+            Self::Lock | Self::Logout | Self::Exec(_) => return Ok(true),
+        };
+
+        Ok(logind::is_allowed(&answer))
+    }
+
+    pub async fn run(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Lock => {
+                // logind would accept it but nothing would lock
+                if !logind::is_lock_watched() {
+                    anyhow::bail!("The lock screen isn't listening to logind");
+                }
+                logind::session().await?.lock().await?
+            }
+            Self::Suspend => logind::manager().await?.suspend(true).await?,
+            Self::Hibernate => logind::manager().await?.hibernate(true).await?,
+            Self::SuspendThenHibernate => {
+                logind::manager()
+                    .await?
+                    .suspend_then_hibernate(true)
+                    .await?
+            }
+            Self::Reboot => logind::manager().await?.reboot(true).await?,
+            Self::RebootToFirmware => {
+                let manager = logind::manager().await?;
+                manager.set_reboot_to_firmware_setup(true).await?;
+                manager.reboot(true).await?
+            }
+            Self::Poweroff => logind::manager().await?.power_off(true).await?,
+            Self::ScheduledPoweroff(minutes) => {
+                let at = SystemTime::now() + Duration::from_secs(minutes * 60);
+                let usec = at.duration_since(UNIX_EPOCH)?.as_micros() as u64;
+                logind::manager()
+                    .await?
+                    .schedule_shutdown("poweroff", usec)
+                    .await?
+            }
+            Self::CancelShutdown => {
+                logind::manager().await?.cancel_scheduled_shutdown().await?;
+            }
+            Self::Logout => {
+                exec(&["systemctl", "--user", "start", "shutdown-graphical.target"]).await?
+            }
+            Self::Exec(argv) => exec(argv).await?,
+        }
+
+        Ok(())
+    }
+}
+
+async fn exec<S: AsRef<str>>(argv: &[S]) -> anyhow::Result<()> {
+    let Some((program, args)) = argv.split_first() else {
+        anyhow::bail!("Empty command");
+    };
+
+    let status = Command::new(program.as_ref())
+        .args(args.iter().map(AsRef::as_ref))
+        .status()
+        .await?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {status}", program.as_ref());
+    }
+
+    Ok(())
+}
+
+/// a button of the powermenu
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerAction {
+    pub label: String,
+    /// font awesome solid icon name
+    pub icon: String,
+    pub command: PowerCommand,
+    /// ask before running, for destructive actions
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+impl PowerAction {
+    fn new(label: &str, icon: &str, command: PowerCommand, confirm: bool) -> Self {
+        Self {
+            label: label.to_owned(),
+            icon: icon.to_owned(),
+            command,
+            confirm,
+        }
+    }
+}
+
+/// how actions that need confirming are confirmed
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Confirm {
+    /// press the button a second time
+    #[default]
+    PressAgain,
+    /// run after counting down this many seconds, unless cancelled
+    Countdown(u64),
+}
+
+pub fn default_actions() -> Vec<PowerAction> {
+    vec![
+        PowerAction::new("Lock", "lock", PowerCommand::Lock, false),
+        PowerAction::new("Sleep", "moon", PowerCommand::Suspend, false),
+        PowerAction::new("Reboot", "rotate", PowerCommand::Reboot, true),
+        PowerAction::new("Shutdown", "power-off", PowerCommand::Poweroff, true),
+        PowerAction::new(
+            "Shutdown in 30 minutes",
+            "hourglass-half",
+            PowerCommand::ScheduledPoweroff(30),
+            false,
+        ),
+        PowerAction::new(
+            "Cancel shutdown",
+            "ban",
+            PowerCommand::CancelShutdown,
+            false,
+        ),
+        PowerAction::new(
+            "Logout",
+            "arrow-right-to-bracket",
+            PowerCommand::Logout,
+            false,
+        ),
+    ]
+}
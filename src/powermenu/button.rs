@@ -13,35 +13,22 @@ use iced_font_awesome::fa_icon_solid;
 
 use super::palette::{LINEAR_BACKGROUND, LINEAR_BACKGROUND_FOCUS};
 
-pub enum Icon {
-    Lock,
-    Sleep,
-    Shutdown,
-    Reboot,
-    Logout,
-}
-
 pub struct PowerButton<T> {
-    pub icon: Icon,
+    /// font awesome solid icon name
+    pub icon: String,
     pub message: T,
 }
 
 impl<T: Clone + 'static> PowerButton<T> {
     pub fn view(&self, is_focused: bool) -> Element<'static, T> {
-        let icon = (match self.icon {
-            Icon::Lock => fa_icon_solid("lock"),
-            Icon::Sleep => fa_icon_solid("moon"),
-            Icon::Reboot => fa_icon_solid("rotate"),
-            Icon::Shutdown => fa_icon_solid("power-off"),
-            Icon::Logout => fa_icon_solid("arrow-right-to-bracket"),
-        })
-        .style(|theme: &Theme| {
-            let palette = theme.palette();
-            text::Style {
-                color: Some(palette.background),
-            }
-        })
-        .size(15.0);
+        let icon = fa_icon_solid(&self.icon)
+            .style(|theme: &Theme| {
+                let palette = theme.palette();
+                text::Style {
+                    color: Some(palette.background),
+                }
+            })
+            .size(15.0);
 
         let button = Button::new(container(icon).center(Fill))
             .style(move |theme: &Theme, status| {
@@ -1,14 +1,18 @@
+use std::time::SystemTime;
+
 use iced::{
-    Length, Task, padding,
+    Length, Subscription, Task, padding, time,
     widget::{
         Button,
         button::{self, Status},
-        container, row,
+        container, row, text,
     },
 };
+use tracing::info;
 
 use crate::{
     feature::Comp,
+    logind,
     theme::CAT_THEME,
     widget::{
         align_center,
@@ -16,11 +20,18 @@ use crate::{
     },
 };
 
-pub struct PowerButton {}
+pub struct PowerButton {
+    /// when logind will shut down, if scheduled
+    scheduled: Option<SystemTime>,
+}
 
 #[derive(Debug, Clone)]
 pub enum Message {
     OnClick,
+    /// pushed by the daemon, which polls logind once for every bar
+    Scheduled(Option<SystemTime>),
+    Tick,
+    CancelShutdown,
 }
 
 impl Comp for PowerButton {
@@ -31,7 +42,37 @@ impl Comp for PowerButton {
         _input: Self::Init,
         _f: impl Fn(Self::Message) -> O + iced::advanced::graphics::futures::MaybeSend + 'static,
     ) -> (Self, iced::Task<O>) {
-        (Self {}, Task::none())
+        (Self { scheduled: None }, Task::none())
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        match self.scheduled {
+            Some(_) => time::every(time::Duration::from_secs(1)).map(|_| Message::Tick),
+            None => Subscription::none(),
+        }
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::Scheduled(scheduled) => {
+                self.scheduled = scheduled;
+                Task::none()
+            }
+            Message::CancelShutdown => Task::future(async {
+                match logind::manager().await {
+                    Ok(manager) => {
+                        if let Err(err) = manager.cancel_scheduled_shutdown().await {
+                            info!("Error cancelling shutdown: {err:?}");
+                        }
+                    }
+                    Err(err) => info!("Error connecting to logind: {err:?}"),
+                }
+                logind::scheduled_shutdown().await.unwrap_or_default()
+            })
+            .map(Message::Scheduled),
+            // tick only re-renders the countdown
+            Message::OnClick | Message::Tick => Task::none(),
+        }
     }
 
     fn view(&self) -> iced::Element<'_, Self::Message> {
@@ -67,6 +108,28 @@ impl Comp for PowerButton {
             })
             .on_press(Message::OnClick);
 
-        align_center!(row![cap, icon]).into()
+        let countdown = self.scheduled.map(|at| {
+            let remaining = at
+                .duration_since(SystemTime::now())
+                .unwrap_or_default()
+                .as_secs();
+
+            let label = text(format!("{}:{:02}", remaining / 60, remaining % 60))
+                .color(theme.peach())
+                .size(spacing.md());
+
+            Button::new(container(label).center_y(Length::Fill))
+                .padding(padding::horizontal(spacing.sm()))
+                .style(move |_, status| button::Style {
+                    background: match status {
+                        Status::Hovered | Status::Pressed => Some(theme.overlay2().into()),
+                        _ => Some(main_clr.into()),
+                    },
+                    ..Default::default()
+                })
+                .on_press(Message::CancelShutdown)
+        });
+
+        align_center!(row![cap].push(countdown).push(icon)).into()
     }
 }
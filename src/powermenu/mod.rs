@@ -1,12 +1,16 @@
+pub mod action;
 mod button;
 pub mod button_comp;
 mod dead_internet;
 mod palette;
 
 use crate::{
+    config::CONFIG,
     feature::{Comp, Feature},
+    logind::{self, Inhibitor},
     powermenu::{
-        button::{Icon, PowerButton},
+        action::{Confirm, PowerAction},
+        button::PowerButton,
         palette::{LINEAR_BACKGROUND, PALETTE},
     },
     types::MonitorId,
@@ -21,16 +25,14 @@ use iced::{
     advanced::graphics::futures::MaybeSend,
     event,
     keyboard::{self, Key, key::Named},
-    padding,
-    widget::{column, container, row, space, stack, text},
+    padding, time,
+    widget::{Container, column, container, row, space, stack, text},
 };
 use iced_layershell::reexport::{self as layer, OutputOption};
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use tracing::{debug, info};
 
-const NUM_OF_BTNS: usize = 5;
-
 #[derive(Debug, Clone)]
 pub struct Init {
     pub args: PowerArgs,
@@ -42,8 +44,8 @@ impl FocusButton {
     fn new() -> Self {
         Self(0)
     }
-    fn next(&mut self) {
-        self.0 = if self.0 == (NUM_OF_BTNS - 1) {
+    fn next(&mut self, len: usize) {
+        self.0 = if self.0 + 1 >= len {
             self.0
         } else {
             self.0 + 1
@@ -57,6 +59,13 @@ impl FocusButton {
     }
 }
 
+/// an action waiting to be confirmed
+struct Pending {
+    idx: usize,
+    /// seconds left when counting down
+    remaining: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     User(String, Option<String>),
@@ -65,12 +74,13 @@ pub enum Message {
     FocusNext,
     FocusPrev,
     Select,
-
-    Lock,
-    Sleep,
-    Shutdown,
-    Reboot,
-    Logout,
+    Pressed(usize),
+    /// whether each action is available
+    Availability(Vec<bool>),
+    Ran(Result<(), String>),
+    Inhibitors(Vec<Inhibitor>),
+    Tick,
+    Escape,
 
     QuitApp,
 }
@@ -81,8 +91,14 @@ pub struct PowerMenu {
     user: Option<String>,
     dead_internet: dead_internet::DeadInternet,
     no_focus: bool,
-    buttons: [PowerButton<Message>; NUM_OF_BTNS],
+    actions: Vec<PowerAction>,
+    buttons: Vec<PowerButton<Message>>,
     focused_btn: FocusButton,
+    pending: Option<Pending>,
+    /// of the last action, shown instead of its label
+    error: Option<String>,
+    /// holding back sleep or shutdown
+    inhibitors: Vec<Inhibitor>,
 }
 
 impl Comp for PowerMenu {
@@ -95,39 +111,59 @@ impl Comp for PowerMenu {
     ) -> (Self, Task<O>) {
         let (dead_internet, task) = dead_internet::DeadInternet::new();
         info!("powermenu: {}", input.args);
+        let actions = CONFIG
+            .powermenu
+            .actions
+            .clone()
+            .unwrap_or_else(action::default_actions);
+        let availability_task = Task::future({
+            let actions = actions.clone();
+            async move {
+                let mut available = Vec::with_capacity(actions.len());
+                for action in actions.iter() {
+                    available.push(action.command.is_available().await.unwrap_or_else(|err| {
+                        info!("Error checking {} availability: {err:?}", action.label);
+                        true
+                    }));
+                }
+                available
+            }
+        })
+        .map(Message::Availability);
+        let inhibitors_task = Task::future(async {
+            logind::list_inhibitors()
+                .await
+                .unwrap_or_else(|err| {
+                    info!("Error listing inhibitors: {err:?}");
+                    Vec::new()
+                })
+                .into_iter()
+                .filter(Inhibitor::blocks_power)
+                .collect()
+        })
+        .map(Message::Inhibitors);
+
+        let mut powermenu = Self {
+            monitor: input.monitor,
+            dryrun: input.args.dryrun,
+            no_focus: input.args.no_focus,
+            dead_internet,
+            user: None,
+            focused_btn: FocusButton::new(),
+            actions: Vec::new(),
+            buttons: Vec::new(),
+            pending: None,
+            error: None,
+            inhibitors: Vec::new(),
+        };
+        powermenu.set_actions(actions);
+
         (
-            Self {
-                monitor: input.monitor,
-                dryrun: input.args.dryrun,
-                no_focus: input.args.no_focus,
-                dead_internet,
-                user: None,
-                focused_btn: FocusButton::new(),
-                buttons: [
-                    PowerButton {
-                        icon: Icon::Lock,
-                        message: Message::Lock,
-                    },
-                    PowerButton {
-                        icon: Icon::Sleep,
-                        message: Message::Sleep,
-                    },
-                    PowerButton {
-                        icon: Icon::Reboot,
-                        message: Message::Reboot,
-                    },
-                    PowerButton {
-                        icon: Icon::Shutdown,
-                        message: Message::Shutdown,
-                    },
-                    PowerButton {
-                        icon: Icon::Logout,
-                        message: Message::Logout,
-                    },
-                ],
-            },
+            powermenu,
             Task::batch(vec![
                 Task::future(get_user()),
+                availability_task,
+                inhibitors_task,
                 task.map(Message::DeadInternet),
             ])
             .map(f),
@@ -139,44 +175,57 @@ impl Comp for PowerMenu {
             Message::QuitApp => Task::none(),
 
             Message::FocusNext => {
-                self.focused_btn.next();
+                self.focused_btn.next(self.actions.len());
+                self.pending = None;
+                self.error = None;
                 Task::none()
             }
             Message::FocusPrev => {
                 self.focused_btn.prev();
+                self.pending = None;
+                self.error = None;
                 Task::none()
             }
-            Message::Select => match self.focused_btn.0 {
-                0 => Task::done(Message::Lock),
-                1 => Task::done(Message::Sleep),
-                2 => Task::done(Message::Reboot),
-                3 => Task::done(Message::Shutdown),
-                4 => Task::done(Message::Logout),
-                _ => Task::none(),
-            },
-
-            Message::Lock => {
-                info!("Lock Request");
-                self.command("loginctl", vec!["lock-session"])
-            }
-            Message::Sleep => {
-                info!("Sleep Request");
-                self.command("systemctl", vec!["suspend"])
-            }
-            Message::Reboot => {
-                info!("Reboot Request");
-                self.command("systemctl", vec!["reboot"])
+            Message::Select => self.press(self.focused_btn.0),
+            Message::Availability(available) => {
+                self.filter_actions(&available);
+                Task::none()
             }
-            Message::Shutdown => {
-                info!("Shutdown Request");
-                self.command("systemctl", vec!["poweroff"])
+            Message::Ran(res) => match res {
+                Ok(()) => Task::done(Message::QuitApp),
+                Err(err) => {
+                    info!("Error running power action: {err}");
+                    self.error = Some(err);
+                    Task::none()
+                }
+            },
+            Message::Inhibitors(inhibitors) => {
+                self.inhibitors = inhibitors;
+                Task::none()
             }
-            Message::Logout => {
-                info!("Logout request");
-                self.command(
-                    "systemctl",
-                    vec!["--user", "start", "shutdown-graphical.target"],
-                )
+            Message::Pressed(idx) => self.press(idx),
+            Message::Tick => match &mut self.pending {
+                Some(Pending {
+                    idx,
+                    remaining: Some(remaining),
+                }) => {
+                    *remaining = remaining.saturating_sub(1);
+                    if *remaining == 0 {
+                        let idx = *idx;
+                        self.pending = None;
+                        self.run(idx)
+                    } else {
+                        Task::none()
+                    }
+                }
+                _ => Task::none(),
+            },
+            Message::Escape => {
+                if self.pending.take().is_some() {
+                    Task::none()
+                } else {
+                    Task::done(Message::QuitApp)
+                }
             }
             Message::User(user, host) => {
                 self.user = host.map(|host| format!("{user}@{host}")).or(Some(user));
@@ -197,7 +246,8 @@ impl Comp for PowerMenu {
                 _ => None,
             })
             .filter_map(|key| match key.as_ref() {
-                Key::Named(Named::Escape) | Key::Character("q") => Some(Message::QuitApp),
+                Key::Named(Named::Escape) => Some(Message::Escape),
+                Key::Character("q") => Some(Message::QuitApp),
                 _ => None,
             });
 
@@ -225,11 +275,19 @@ impl Comp for PowerMenu {
             .subscriptions()
             .map(Message::DeadInternet);
 
+        let countdown = match self.pending {
+            Some(Pending {
+                remaining: Some(_), ..
+            }) => time::every(time::Duration::from_secs(1)).map(|_| Message::Tick),
+            _ => Subscription::none(),
+        };
+
         Subscription::batch(vec![
             quit_bindings,
             nav_bindings,
             app_events,
             dead_internet_subs,
+            countdown,
         ])
     }
 
@@ -249,34 +307,36 @@ impl Comp for PowerMenu {
         let user_container = self
             .user
             .as_ref()
-            .map(|user| {
-                let inner_box =
-                    container(text(user.clone()))
-                        .padding(10)
-                        .style(|_| container::Style {
-                            background: Some(LINEAR_BACKGROUND),
-                            border: Border::default()
-                                .rounded(1.0)
-                                .color(palette.background)
-                                .width(2.0),
-                            ..Default::default()
-                        });
-                let outer_box = container(inner_box)
-                    .style(|_| container::Style {
-                        border: Border::default()
-                            .rounded(1.0)
-                            .color(palette.text)
-                            .width(2.0),
-                        text_color: Some(palette.background),
-                        ..Default::default()
-                    })
-                    .padding(1);
-                container(outer_box).width(Fill)
-            })
-            .or(Some(container(space()).width(Fill)))
-            .map(|cont| row![cont].padding(Padding::from([25, 25])));
-
-        let content = column![buttons, user_container].padding(Padding::default().horizontal(10));
+            .map(|user| label_box(user.clone()))
+            .unwrap_or(container(space()))
+            .width(Fill);
+        let status_container = label_box(self.status());
+
+        let user_container =
+            row![user_container, status_container].padding(Padding::from([25, 25]));
+
+        let inhibitors = (!self.inhibitors.is_empty()).then(|| {
+            let lines = self
+                .inhibitors
+                .iter()
+                .map(|inhibitor| {
+                    format!(
+                        "{}: {} ({}, {})",
+                        inhibitor.who, inhibitor.why, inhibitor.what, inhibitor.mode
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            label_box(format!("Inhibited by\n{lines}"))
+                .width(Fill)
+                .padding(Padding::default().horizontal(25).top(25))
+        });
+
+        let content = column![buttons]
+            .push(inhibitors)
+            .push(user_container)
+            .padding(Padding::default().horizontal(10));
 
         // main layout
         let main_layout = container(content)
@@ -302,23 +362,148 @@ impl Comp for PowerMenu {
 }
 
 impl PowerMenu {
-    fn command(&self, program: &str, args: Vec<&str>) -> Task<Message> {
-        let mut cmd = std::process::Command::new(program);
-        cmd.args(&args);
+    /// run the action, or wait for it to be confirmed
+    fn press(&mut self, idx: usize) -> Task<Message> {
+        let Some(action) = self.actions.get(idx) else {
+            return Task::none();
+        };
+        self.focused_btn.0 = idx;
+        self.error = None;
 
+        let is_confirming = self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.idx == idx);
+        if !action.confirm || is_confirming {
+            self.pending = None;
+            return self.run(idx);
+        }
+
+        self.pending = Some(Pending {
+            idx,
+            remaining: match CONFIG.powermenu.confirm {
+                Confirm::PressAgain => None,
+                Confirm::Countdown(secs) => Some(secs),
+            },
+        });
+        Task::none()
+    }
+
+    fn run(&self, idx: usize) -> Task<Message> {
+        let Some(action) = self.actions.get(idx) else {
+            return Task::none();
+        };
+
+        info!("{} Request", action.label);
         if self.dryrun {
-            debug!("{cmd:#?}");
-            let args_print = args.join(" ");
-            println!("dryrun: {program} {args_print}");
-        } else {
-            cmd.output()
-                .map_err(|err| format!("Error running command: {err:?}"))
-                .unwrap();
+            println!("dryrun: {:?}", action.command);
+            return Task::done(Message::QuitApp);
+        }
+
+        let command = action.command.clone();
+        Task::future(async move { command.run().await.map_err(|err| format!("{err:#}")) })
+            .map(Message::Ran)
+    }
+
+    /// the focused action, or what confirming it takes
+    fn status(&self) -> String {
+        let label = |idx: usize| {
+            self.actions
+                .get(idx)
+                .map(|action| action.label.clone())
+                .unwrap_or_default()
+        };
+
+        if let Some(err) = &self.error {
+            return format!("Error: {err}");
         }
-        Task::done(Message::QuitApp)
+
+        match &self.pending {
+            Some(Pending {
+                idx,
+                remaining: None,
+            }) => format!("Press again to {}", label(*idx).to_lowercase()),
+            Some(Pending {
+                idx,
+                remaining: Some(remaining),
+            }) => format!("{} in {remaining}s, esc to cancel", label(*idx)),
+            None => label(self.focused_btn.0),
+        }
+    }
+
+    fn set_actions(&mut self, actions: Vec<PowerAction>) {
+        self.buttons = actions
+            .iter()
+            .enumerate()
+            .map(|(idx, action)| PowerButton {
+                icon: action.icon.clone(),
+                message: Message::Pressed(idx),
+            })
+            .collect();
+        self.actions = actions;
+        self.focused_btn = FocusButton::new();
+        self.pending = None;
+    }
+
+    /// drop the unavailable actions, keeping the focus and any pending
+    /// confirmation on the same actions, a key press may have beaten the check
+    fn filter_actions(&mut self, available: &[bool]) {
+        let is_available = |idx: usize| available.get(idx).copied().unwrap_or(true);
+        // an action's index once the unavailable ones before it are gone
+        let new_idx = |idx: usize| (0..idx).filter(|idx| is_available(*idx)).count();
+
+        let focused = self.focused_btn.0;
+        let pending = self
+            .pending
+            .take()
+            .filter(|pending| is_available(pending.idx))
+            .map(|pending| Pending {
+                idx: new_idx(pending.idx),
+                ..pending
+            });
+
+        let actions = self
+            .actions
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| is_available(*idx))
+            .map(|(_, action)| action.clone())
+            .collect();
+        self.set_actions(actions);
+
+        // an unavailable focused action passes the focus to the next one
+        self.focused_btn.0 = new_idx(focused).min(self.actions.len().saturating_sub(1));
+        self.pending = pending;
     }
 }
 
+/// text in the boxed style of the powermenu
+fn label_box<'a>(label: String) -> Container<'a, Message> {
+    let palette = &PALETTE;
+    let inner_box = container(text(label))
+        .padding(10)
+        .style(|_| container::Style {
+            background: Some(LINEAR_BACKGROUND),
+            border: Border::default()
+                .rounded(1.0)
+                .color(palette.background)
+                .width(2.0),
+            ..Default::default()
+        });
+    let outer_box = container(inner_box)
+        .style(|_| container::Style {
+            border: Border::default()
+                .rounded(1.0)
+                .color(palette.text)
+                .width(2.0),
+            text_color: Some(palette.background),
+            ..Default::default()
+        })
+        .padding(1);
+
+    container(outer_box)
+}
+
 async fn get_user() -> Message {
     let user = Command::new("whoami")
         .output()
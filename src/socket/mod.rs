@@ -13,13 +13,15 @@ use tokio::{
 use tokio_stream::wrappers::{LinesStream, UnixListenerStream};
 use tracing::info;
 
-use crate::{osd::OsdCommand, powermenu::PowerArgs};
+use crate::{launcher::LauncherArgs, osd::OsdCommand, powermenu::PowerArgs};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum Request {
-    Launcher,
+    Launcher(LauncherArgs),
     Osd(OsdCommand),
     PowerMenu(PowerArgs),
+    Lock,
+    Caffeine,
 }
 
 #[derive(Deref, DerefMut, From)]
@@ -51,8 +53,8 @@ fn connect_and_send(req: &str) -> anyhow::Result<()> {
     })
 }
 
-pub fn send_launcher_req() -> anyhow::Result<()> {
-    let req = Request::Launcher.to_string_line()?;
+pub fn send_launcher_req(args: LauncherArgs) -> anyhow::Result<()> {
+    let req = Request::Launcher(args).to_string_line()?;
     connect_and_send(&req)
 }
 
@@ -66,6 +68,16 @@ pub fn send_powermenu_req(args: PowerArgs) -> anyhow::Result<()> {
     connect_and_send(&req)
 }
 
+pub fn send_lock_req() -> anyhow::Result<()> {
+    let req = Request::Lock.to_string_line()?;
+    connect_and_send(&req)
+}
+
+pub fn send_caffeine_req() -> anyhow::Result<()> {
+    let req = Request::Caffeine.to_string_line()?;
+    connect_and_send(&req)
+}
+
 pub fn listen() -> IcedSocket {
     get_path()
         .and_then(|path| {
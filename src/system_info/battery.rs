@@ -1,67 +1,296 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
 use iced::futures::{
     StreamExt,
     stream::{self, BoxStream},
 };
+use itertools::Itertools;
 use tokio::fs;
 
+use crate::config::CONFIG;
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+pub const LOW_CAP: f64 = 10.0;
+
 #[derive(Debug, Clone)]
 pub enum BatteryState {
     Full,
     Charging(f64),
     Discharging(f64),
     Low(f64),
+    /// plugged in but holding, e.g. at a charge threshold
+    NotCharging(f64),
     None,
 }
 
-async fn has_battery(bat: &str) -> anyhow::Result<bool> {
-    let bat_dir = format!("/sys/class/power_supply/{}", bat);
-    let mut entries = fs::read_dir(bat_dir).await?;
-    Ok(entries.next_entry().await?.is_some())
+/// the batteries combined
+#[derive(Debug, Clone)]
+pub struct BatteryInfo {
+    pub state: BatteryState,
+    /// whether an ac adapter is plugged in
+    pub ac_online: bool,
+    /// until empty while discharging, until full while charging
+    pub time_left: Option<Duration>,
 }
 
-async fn get_cap(bat: &str) -> f64 {
-    let charge_now_path = format!("/sys/class/power_supply/{}/charge_now", bat);
-    let charge_full_path = format!("/sys/class/power_supply/{}/charge_full", bat);
-    let charge_now = fs::read_to_string(&charge_now_path)
-        .await
-        .ok()
-        .and_then(|s| s.trim().parse::<f64>().ok());
+impl Default for BatteryInfo {
+    fn default() -> Self {
+        Self {
+            state: BatteryState::None,
+            ac_online: false,
+            time_left: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    /// µWh and µW
+    Energy,
+    /// µAh and µA, when the voltage to convert it is unknown
+    Charge,
+}
+
+/// one battery's sysfs readings
+struct Reading {
+    now_full: Option<(f64, f64)>,
+    /// power or current
+    rate: Option<f64>,
+    unit: Unit,
+    capacity: Option<f64>,
+    status: String,
+}
 
-    let charge_full = fs::read_to_string(&charge_full_path)
+impl Reading {
+    fn percent(&self) -> Option<f64> {
+        match self.now_full {
+            Some((now, full)) if full > 0.0 => Some(now / full * 100.0),
+            _ => self.capacity,
+        }
+    }
+}
+
+async fn read_str(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
         .await
         .ok()
-        .and_then(|s| s.trim().parse::<f64>().ok());
+        .map(|s| s.trim().to_owned())
+}
+
+async fn read_f64(path: &Path) -> Option<f64> {
+    read_str(path).await?.parse().ok()
+}
+
+async fn supplies() -> Vec<PathBuf> {
+    let Ok(mut entries) = fs::read_dir(POWER_SUPPLY_DIR).await else {
+        return Vec::new();
+    };
+
+    let mut supplies = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        supplies.push(entry.path());
+    }
+    supplies
+}
+
+/// system batteries, or just `bat` when named
+/// batteries of e.g. a mouse have the "Device" scope and are skipped
+async fn batteries(supplies: &[PathBuf], bat: Option<&str>) -> Vec<PathBuf> {
+    let mut batteries = Vec::new();
+    for supply in supplies {
+        let is_named = bat.is_none_or(|bat| supply.file_name().is_some_and(|name| name == bat));
+        if is_named
+            && read_str(&supply.join("type")).await.as_deref() == Some("Battery")
+            && read_str(&supply.join("scope")).await.as_deref() != Some("Device")
+        {
+            batteries.push(supply.clone());
+        }
+    }
+    batteries
+}
 
-    match (charge_now, charge_full) {
-        (Some(now), Some(full)) if full > 0.0 => ((now / full) * 100.0).round(),
-        _ => 100.,
+/// any online supply that isn't a battery, e.g. "Mains", or "USB" and
+/// "USB_PD" for usb-c chargers
+async fn is_ac_online(supplies: &[PathBuf]) -> bool {
+    for supply in supplies {
+        let is_battery = read_str(&supply.join("type")).await.as_deref() == Some("Battery");
+        if !is_battery
+            && read_str(&supply.join("scope")).await.as_deref() != Some("Device")
+            && read_str(&supply.join("online")).await.as_deref() == Some("1")
+        {
+            return true;
+        }
     }
+    false
 }
 
-async fn get_status(bat: &str) -> BatteryState {
-    let cap = get_cap(bat).await;
-    let status_path = format!("/sys/class/power_supply/{}/status", bat);
-    let status = fs::read_to_string(&status_path)
+async fn read(bat: &Path) -> Reading {
+    let power = read_f64(&bat.join("power_now")).await;
+    let current = read_f64(&bat.join("current_now")).await;
+    // µV, converts charge to energy when a driver only reports charge
+    let voltage = read_f64(&bat.join("voltage_now"))
         .await
-        .unwrap_or_else(|_| "Unknown".to_string());
+        .filter(|voltage| *voltage > 0.0);
+    let design_voltage = read_f64(&bat.join("voltage_min_design"))
+        .await
+        .filter(|voltage| *voltage > 0.0);
+
+    let energy = (
+        read_f64(&bat.join("energy_now")).await,
+        read_f64(&bat.join("energy_full")).await,
+    );
+    let (now_full, rate, unit) = match energy {
+        (Some(now), Some(full)) => (
+            Some((now, full)),
+            power.or_else(|| Some(current? * voltage? / 1_000_000.0)),
+            Unit::Energy,
+        ),
+        _ => match (
+            read_f64(&bat.join("charge_now")).await,
+            read_f64(&bat.join("charge_full")).await,
+            design_voltage.or(voltage),
+        ) {
+            (Some(now), Some(full), Some(charge_voltage)) => (
+                Some((
+                    now * charge_voltage / 1_000_000.0,
+                    full * charge_voltage / 1_000_000.0,
+                )),
+                power.or_else(|| Some(current? * voltage.unwrap_or(charge_voltage) / 1_000_000.0)),
+                Unit::Energy,
+            ),
+            (Some(now), Some(full), None) => (Some((now, full)), current, Unit::Charge),
+            _ => (None, None, Unit::Energy),
+        },
+    };
+
+    Reading {
+        now_full,
+        unit,
+        // some drivers report a negative current while discharging
+        rate: rate.map(f64::abs).filter(|rate| *rate > 0.0),
+        capacity: read_f64(&bat.join("capacity")).await,
+        status: read_str(&bat.join("status"))
+            .await
+            .unwrap_or_else(|| "Unknown".to_owned()),
+    }
+}
+
+/// whether the readings can be summed
+fn is_same_unit(readings: &[Reading]) -> bool {
+    readings.iter().map(|reading| reading.unit).all_equal()
+}
+
+/// percent of the combined capacity, falls back to the mean percentage when
+/// a battery doesn't report its charge or the units differ
+fn combined_cap(readings: &[Reading]) -> f64 {
+    let totals = readings
+        .iter()
+        .map(|reading| reading.now_full)
+        .try_fold((0.0, 0.0), |(now, full), now_full| {
+            now_full.map(|(bat_now, bat_full)| (now + bat_now, full + bat_full))
+        })
+        .filter(|_| is_same_unit(readings));
+
+    let cap = match totals {
+        Some((now, full)) if full > 0.0 => now / full * 100.0,
+        _ => {
+            let percents: Vec<f64> = readings.iter().filter_map(Reading::percent).collect();
+            if percents.is_empty() {
+                100.0
+            } else {
+                percents.iter().sum::<f64>() / percents.len() as f64
+            }
+        }
+    };
+    cap.clamp(0.0, 100.0).round()
+}
+
+/// time until empty or full at the combined rate
+fn time_left(readings: &[Reading], is_charging: bool) -> Option<Duration> {
+    if !is_same_unit(readings) {
+        return None;
+    }
+
+    let (now, full, rate) = readings
+        .iter()
+        .filter_map(|reading| Some((reading.now_full?, reading.rate?)))
+        .fold(
+            (0.0, 0.0, 0.0),
+            |(now, full, rate), ((bat_now, bat_full), bat_rate)| {
+                (now + bat_now, full + bat_full, rate + bat_rate)
+            },
+        );
+    if rate <= 0.0 {
+        return None;
+    }
+
+    let left = if is_charging { full - now } else { now };
+    Duration::try_from_secs_f64(left.max(0.0) / rate * 3600.0).ok()
+}
+
+async fn get_info(bat: Option<&str>) -> BatteryInfo {
+    let supplies = supplies().await;
+    let ac_online = is_ac_online(&supplies).await;
+
+    let mut readings = Vec::new();
+    for bat in batteries(&supplies, bat).await {
+        readings.push(read(&bat).await);
+    }
+    if readings.is_empty() {
+        return BatteryInfo {
+            ac_online,
+            ..Default::default()
+        };
+    }
+
+    let cap = combined_cap(&readings);
+    let has_status = |status: &str| readings.iter().any(|reading| reading.status == status);
+    let all_status = |statuses: &[&str]| {
+        readings
+            .iter()
+            .all(|reading| statuses.contains(&reading.status.as_str()))
+    };
+
+    let state = if has_status("Charging") {
+        BatteryState::Charging(cap)
+    } else if all_status(&["Full"]) {
+        BatteryState::Full
+    } else if has_status("Discharging") || (!ac_online && !all_status(&["Full", "Not charging"])) {
+        if cap < CONFIG.battery.low.unwrap_or(LOW_CAP) {
+            BatteryState::Low(cap)
+        } else {
+            BatteryState::Discharging(cap)
+        }
+    } else {
+        BatteryState::NotCharging(cap)
+    };
+
+    let time_left = match state {
+        BatteryState::Charging(_) => time_left(&readings, true),
+        BatteryState::Discharging(_) | BatteryState::Low(_) => time_left(&readings, false),
+        _ => None,
+    };
 
-    match status.trim() {
-        "Full" => BatteryState::Full,
-        "Charging" => BatteryState::Charging(cap),
-        "Discharging" if cap < 10.0 => BatteryState::Low(cap),
-        _ => BatteryState::Discharging(cap),
+    BatteryInfo {
+        state,
+        ac_online,
+        time_left,
     }
 }
 
 #[derive(Hash)]
 pub struct ListenData {
     pub delay: u64,
-    pub bat: String,
+    /// None combines every battery
+    pub bat: Option<String>,
 }
 
 pub fn listen<'a>(
     ListenData { delay, bat }: &ListenData,
-) -> BoxStream<'a, anyhow::Result<BatteryState>> {
+) -> BoxStream<'a, anyhow::Result<BatteryInfo>> {
     let delay = *delay;
     let bat = bat.clone();
     stream::repeat(())
@@ -69,12 +298,7 @@ pub fn listen<'a>(
             let bat = bat.clone();
             async move {
                 tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-                if has_battery(&bat).await.is_ok_and(|x| x) {
-                    let stat = get_status(&bat).await;
-                    Ok(stat)
-                } else {
-                    Ok(BatteryState::None)
-                }
+                Ok(get_info(bat.as_deref()).await)
             }
         })
         .boxed()
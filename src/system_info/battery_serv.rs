@@ -0,0 +1,174 @@
+//! reads the batteries for the bars, warns as they run down and suspends past
+//! the configured threshold
+use std::time::Duration;
+
+use iced::{Subscription, Task, advanced::graphics::futures::MaybeSend};
+use tracing::info;
+
+use crate::{
+    config::CONFIG,
+    feature::Service,
+    logind,
+    system_info::battery::{self as bat, BatteryInfo, BatteryState},
+};
+
+const CRITICAL_CAP: f64 = 5.0;
+/// after resuming below the suspend threshold, time to plug in before it
+/// suspends again
+const SUSPEND_GRACE: Duration = Duration::from_secs(120);
+/// time between the suspend warning and suspending, to read it and plug in
+const SUSPEND_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Warning {
+    Low,
+    Critical,
+    Suspend,
+}
+
+impl Warning {
+    fn for_cap(cap: f64) -> Option<Self> {
+        let config = &CONFIG.battery;
+        if config.suspend_at.is_some_and(|suspend_at| cap < suspend_at) {
+            Some(Self::Suspend)
+        } else if cap < config.critical.unwrap_or(CRITICAL_CAP) {
+            Some(Self::Critical)
+        } else if cap < config.low.unwrap_or(bat::LOW_CAP) {
+            Some(Self::Low)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// the daemon pushes it to the bars
+    OnBat(BatteryInfo),
+    /// handled by the daemon
+    Warn(Warning, f64),
+    /// suspend unless plugged in since the warning, tagged with the warning
+    /// it was scheduled by
+    Suspend(usize),
+    Resumed,
+    /// allow suspending again after resuming
+    Rearm,
+}
+
+pub struct BatteryServ {
+    info: BatteryInfo,
+    /// the last warning raised since the battery was charging
+    warned: Option<Warning>,
+    /// bumped on every suspend warning, so a stale delayed suspend is dropped
+    suspend_gen: usize,
+}
+
+impl BatteryServ {
+    pub fn info(&self) -> &BatteryInfo {
+        &self.info
+    }
+}
+
+impl Service for BatteryServ {
+    type Message = Message;
+    type Init = ();
+
+    fn new<O: MaybeSend + 'static>(
+        _input: Self::Init,
+        _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+    ) -> (Self, Task<O>) {
+        Self {
+            info: BatteryInfo::default(),
+            warned: None,
+            suspend_gen: 0,
+        }
+        .to_tuple()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let bat_sub = Subscription::run_with(
+            bat::ListenData {
+                delay: 1000,
+                bat: CONFIG.battery.name.clone(),
+            },
+            bat::listen,
+        )
+        .filter_map(|res| match res {
+            Ok(bat) => Some(Message::OnBat(bat)),
+            Err(err) => {
+                info!("Error getting bat state {err:?}");
+                None
+            }
+        });
+
+        let resume_sub = Subscription::run(logind::watch_resume).map(|_| Message::Resumed);
+
+        Subscription::batch([bat_sub, resume_sub])
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            Message::OnBat(info) => {
+                let cap = match info.state {
+                    BatteryState::Discharging(cap) | BatteryState::Low(cap) => Some(cap),
+                    _ => None,
+                };
+                self.info = info;
+
+                let Some(cap) = cap else {
+                    self.warned = None;
+                    return Task::none();
+                };
+                let Some(warning) = Warning::for_cap(cap) else {
+                    return Task::none();
+                };
+                // only escalate, not every tick
+                if self.warned.is_some_and(|warned| warned >= warning) {
+                    return Task::none();
+                }
+                self.warned = Some(warning);
+                info!("battery warning: {warning:?} at {cap}%");
+
+                let warn = Task::done(Message::Warn(warning, cap));
+                if warning != Warning::Suspend {
+                    return warn;
+                }
+
+                self.suspend_gen += 1;
+                let suspend_gen = self.suspend_gen;
+                let suspend = Task::future(tokio::time::sleep(SUSPEND_DELAY))
+                    .map(move |_| Message::Suspend(suspend_gen));
+                Task::batch([warn, suspend])
+            }
+            Message::Suspend(suspend_gen) => {
+                // plugged in, or warned again, since it was scheduled
+                if suspend_gen != self.suspend_gen || self.warned != Some(Warning::Suspend) {
+                    return Task::none();
+                }
+                info!("suspending on low battery");
+
+                Task::future(async {
+                    let res = match logind::manager().await {
+                        Ok(manager) => manager.suspend(false).await,
+                        Err(err) => Err(err),
+                    };
+                    if let Err(err) = res {
+                        info!("Error suspending: {err:?}");
+                    }
+                })
+                .discard()
+            }
+            // still below the threshold, suspend again unless plugged in
+            Message::Resumed => {
+                Task::future(tokio::time::sleep(SUSPEND_GRACE)).map(|_| Message::Rearm)
+            }
+            Message::Rearm => {
+                if self.warned == Some(Warning::Suspend) {
+                    self.warned = Some(Warning::Critical);
+                }
+                Task::none()
+            }
+            Message::Warn(..) => Task::none(),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod battery;
+pub mod battery_serv;
 mod cpu_temp;
 use iced::{
     Subscription, Task,
@@ -11,7 +12,7 @@ use lucide_icons::Icon;
 use sysinfo::{CpuRefreshKind, DiskRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
 use tracing::info;
 
-pub use battery::BatteryState;
+pub use battery::{BatteryInfo, BatteryState};
 
 use crate::{
     feature::Comp,
@@ -31,21 +32,17 @@ const BYTES_IN_GIG: u64 = 1_073_741_824;
 #[derive(Debug, Clone)]
 pub enum Message {
     OnCpuTemp(f32),
-    OnBat(bat::BatteryState),
+    /// pushed by the daemon from the battery service
+    OnBat(bat::BatteryInfo),
     OnTick,
 }
 
-pub struct Init {
-    pub bat_name: Option<String>,
-}
-
 pub struct SysInfoComp {
     disks: Disks,
     system: System,
     load: f64,
     cpu_temp: f32,
-    bat_name: String,
-    bat_stat: BatteryState,
+    bat: BatteryInfo,
 }
 
 impl SysInfoComp {
@@ -62,16 +59,24 @@ impl SysInfoComp {
     }
 
     pub fn bat_stat(&self) -> &BatteryState {
-        &self.bat_stat
+        &self.bat.state
+    }
+
+    pub fn is_ac_online(&self) -> bool {
+        self.bat.ac_online
+    }
+
+    pub fn bat_time_left(&self) -> Option<time::Duration> {
+        self.bat.time_left
     }
 }
 
 impl Comp for SysInfoComp {
     type Message = Message;
-    type Init = Init;
+    type Init = ();
 
     fn new<O: MaybeSend + 'static>(
-        input: Self::Init,
+        _input: Self::Init,
         _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
     ) -> (Self, Task<O>) {
         let system = System::new_with_specifics(
@@ -86,8 +91,7 @@ impl Comp for SysInfoComp {
             system,
             load: 0.,
             cpu_temp: 0.,
-            bat_stat: BatteryState::None,
-            bat_name: input.bat_name.unwrap_or_default(),
+            bat: BatteryInfo::default(),
         }
         .to_tuple()
     }
@@ -102,24 +106,9 @@ impl Comp for SysInfoComp {
                 }
             });
 
-        let bat_stat = Subscription::run_with(
-            bat::ListenData {
-                delay: 1000,
-                bat: self.bat_name.clone(),
-            },
-            bat::listen,
-        )
-        .filter_map(|res| match res {
-            Ok(bat) => Some(Message::OnBat(bat)),
-            Err(err) => {
-                info!("Error getting bat state {err:?}");
-                None
-            }
-        });
-
         let refresh_sub = time::every(time::Duration::from_millis(750)).map(|_| Message::OnTick);
 
-        Subscription::batch([avg_temp_sub, refresh_sub, bat_stat])
+        Subscription::batch([avg_temp_sub, refresh_sub])
     }
 
     fn update(&mut self, message: Self::Message) -> iced::Task<Self::Message> {
@@ -128,8 +117,8 @@ impl Comp for SysInfoComp {
                 self.cpu_temp = cpu_temp;
                 Task::none()
             }
-            Message::OnBat(bat_state) => {
-                self.bat_stat = bat_state;
+            Message::OnBat(bat) => {
+                self.bat = bat;
                 Task::none()
             }
             Message::OnTick => {
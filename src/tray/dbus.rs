@@ -1,6 +1,16 @@
 use derive_more::{Deref, DerefMut, Display, From};
-use iced::{advanced::image, futures::StreamExt};
-use std::{collections::HashSet, time::Duration};
+use iced::{
+    advanced::image,
+    futures::{StreamExt, stream::BoxStream},
+};
+use std::{
+    collections::HashSet,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use tracing::{info, trace, warn};
 use zbus::{
     Connection, Result,
@@ -13,18 +23,26 @@ use zbus::{
     zvariant::{self, OwnedObjectPath, OwnedValue, Type},
 };
 
-use crate::widget::fdo_icons::FdIcon;
+use crate::widget::fdo_icons::{self, FdIcon};
 
 const NAME: WellKnownName =
     WellKnownName::from_static_str_unchecked("org.kde.StatusNotifierWatcher");
 const OBJECT_PATH: &str = "/StatusNotifierWatcher";
 
+/// used to keep the host names unique when the tray stream restarts
+static HOST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// reference point for the monotonic menu event timestamps
+static EVENT_EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
+
 #[derive(Debug, Default)]
 pub struct StatusNotifierWatcher {
     items: Vec<(UniqueName<'static>, String)>,
 }
 
 impl StatusNotifierWatcher {
+    /// start the watcher, if another watcher already owns the bus name
+    /// register with it as a host instead
     pub async fn start_server() -> anyhow::Result<Connection> {
         let connection = zbus::connection::Connection::session().await?;
         let watcher = StatusNotifierWatcher::default();
@@ -37,9 +55,15 @@ impl StatusNotifierWatcher {
         let dbus_proxy = DBusProxy::new(&connection).await?;
         let name_owner_changed_stream = dbus_proxy.receive_name_owner_changed().await?;
 
-        let flags = RequestNameFlags::AllowReplacement.into();
-        if dbus_proxy.request_name(NAME, flags).await? == RequestNameReply::InQueue {
-            warn!("Bus name '{NAME}' already owned");
+        let flags = RequestNameFlags::AllowReplacement | RequestNameFlags::DoNotQueue;
+        if dbus_proxy.request_name(NAME, flags).await? == RequestNameReply::Exists {
+            warn!("Bus name '{NAME}' already owned, acting as a host of the existing watcher");
+            connection
+                .object_server()
+                .remove::<StatusNotifierWatcher, _>(OBJECT_PATH)
+                .await?;
+            Self::register_host(&connection).await?;
+            return Ok(connection);
         }
 
         let internal_connection = connection.clone();
@@ -65,6 +89,10 @@ impl StatusNotifierWatcher {
                             } else if have_bus_name {
                                 info!("Lost bus name: {NAME}");
                                 have_bus_name = false;
+                                // keep showing items through the watcher that replaced us
+                                if let Err(e) = Self::register_host(&internal_connection).await {
+                                    info!("Failed to register as host: {e}");
+                                }
                             }
                         } else if let BusName::Unique(name) = &args.name {
                             let mut interface = internal_interface.get_mut().await;
@@ -104,6 +132,41 @@ impl StatusNotifierWatcher {
         Ok(connection)
     }
 
+    /// register a host with the watcher that currently owns the bus name
+    async fn register_host(conn: &Connection) -> anyhow::Result<()> {
+        let host_name = format!(
+            "org.kde.StatusNotifierHost-{}-{}",
+            std::process::id(),
+            HOST_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        conn.request_name(host_name.as_str()).await?;
+
+        StatusNotifierWatcherProxy::new(conn)
+            .await?
+            .register_status_notifier_host(&host_name)
+            .await?;
+
+        info!("Registered tray host: {host_name}");
+        Ok(())
+    }
+
+    /// emits when the watcher owning the bus name goes away
+    pub async fn watcher_lost_stream(conn: &Connection) -> anyhow::Result<BoxStream<'static, ()>> {
+        let stream = DBusProxy::new(conn)
+            .await?
+            .receive_name_owner_changed_with_args(&[(0, NAME.as_str())])
+            .await?
+            .filter_map(|evt| async move {
+                evt.args()
+                    .ok()
+                    .filter(|args| args.new_owner.is_none())
+                    .map(|_| ())
+            })
+            .boxed();
+
+        Ok(stream)
+    }
+
     async fn prune_stale_items(
         conn: &Connection,
         interface: &zbus::object_server::InterfaceRef<StatusNotifierWatcher>,
@@ -293,6 +356,10 @@ pub trait StatusNotifierItem {
     #[zbus(property)]
     fn icon_pixmap(&self) -> zbus::Result<Vec<Icon>>;
 
+    /// extra dir to look up icon names in
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> zbus::Result<String>;
+
     #[zbus(property)]
     fn menu(&self) -> zbus::Result<OwnedObjectPath>;
 
@@ -314,11 +381,14 @@ pub trait StatusNotifierItem {
     #[zbus(signal)]
     fn new_tool_tip(&self) -> zbus::Result<()>;
 
+    #[zbus(signal)]
+    fn new_icon_theme_path(&self, icon_theme_path: String) -> zbus::Result<()>;
+
     #[zbus(signal)]
     fn new_status(&self, status: String) -> zbus::Result<()>;
 }
 
-#[derive(Clone, Copy, Debug, Type, Deref, DerefMut, From, PartialEq, Eq, Display)]
+#[derive(Clone, Copy, Debug, Type, Deref, DerefMut, From, PartialEq, Eq, Hash, Display)]
 pub struct TrayMenuItemId(i32);
 
 /// The menu layout
@@ -333,6 +403,18 @@ pub struct TrayLayout {
     pub children: Vec<TrayLayout>,
 }
 
+impl TrayLayout {
+    /// find the menu item with the given id in the layout tree
+    pub fn find_mut(&mut self, id: TrayMenuItemId) -> Option<&mut TrayLayout> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children
+            .iter_mut()
+            .find_map(|child| child.find_mut(id))
+    }
+}
+
 impl<'a> serde::Deserialize<'a> for TrayLayout {
     fn deserialize<D: serde::Deserializer<'a>>(
         deserializer: D,
@@ -363,6 +445,175 @@ pub struct TrayLayoutProps {
 
     #[zvariant(rename = "toggle-state")]
     pub toggle_state: Option<i32>,
+
+    pub enabled: Option<bool>,
+
+    pub visible: Option<bool>,
+
+    #[zvariant(rename = "icon-name")]
+    pub icon_name: Option<String>,
+
+    /// png encoded icon
+    #[zvariant(rename = "icon-data")]
+    pub icon_data: Option<Vec<u8>>,
+
+    /// list of key chords, e.g. [["Control", "q"]]
+    pub shortcut: Option<Vec<Vec<String>>>,
+}
+
+impl TrayLayoutProps {
+    /// apply a partial property update, keeping the props that are not part of it
+    pub fn merge(&mut self, update: TrayLayoutProps) {
+        let TrayLayoutProps {
+            label,
+            children_display,
+            type_,
+            toggle_type,
+            toggle_state,
+            enabled,
+            visible,
+            icon_name,
+            icon_data,
+            shortcut,
+        } = update;
+
+        self.label = label.or(self.label.take());
+        self.children_display = children_display.or(self.children_display.take());
+        self.type_ = type_.or(self.type_.take());
+        self.toggle_type = toggle_type.or(self.toggle_type.take());
+        self.toggle_state = toggle_state.or(self.toggle_state.take());
+        self.enabled = enabled.or(self.enabled.take());
+        self.visible = visible.or(self.visible.take());
+        self.icon_name = icon_name.or(self.icon_name.take());
+        self.icon_data = icon_data.or(self.icon_data.take());
+        self.shortcut = shortcut.or(self.shortcut.take());
+    }
+
+    /// reset the named props to their defaults
+    pub fn remove(&mut self, names: &[String]) {
+        for name in names {
+            match name.as_str() {
+                "label" => self.label = None,
+                "children-display" => self.children_display = None,
+                "type" => self.type_ = None,
+                "toggle-type" => self.toggle_type = None,
+                "toggle-state" => self.toggle_state = None,
+                "enabled" => self.enabled = None,
+                "visible" => self.visible = None,
+                "icon-name" => self.icon_name = None,
+                "icon-data" => self.icon_data = None,
+                "shortcut" => self.shortcut = None,
+                _ => (),
+            }
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible.unwrap_or(true)
+    }
+
+    pub fn is_separator(&self) -> bool {
+        self.type_.as_deref() == Some("separator")
+    }
+
+    pub fn is_submenu(&self) -> bool {
+        self.children_display.as_deref() == Some("submenu")
+    }
+
+    /// label with the mnemonic underscores removed
+    pub fn label_text(&self) -> Option<String> {
+        self.label.as_deref().map(strip_mnemonic)
+    }
+
+    /// human readable shortcut, e.g. "Ctrl+Q"
+    pub fn shortcut_text(&self) -> Option<String> {
+        self.shortcut
+            .as_ref()
+            .filter(|chords| !chords.is_empty())
+            .map(|chords| {
+                chords
+                    .iter()
+                    .map(|chord| {
+                        chord
+                            .iter()
+                            .map(|key| match key.as_str() {
+                                "Control" => "Ctrl".to_owned(),
+                                key if key.chars().count() == 1 => key.to_uppercase(),
+                                key => key.to_owned(),
+                            })
+                            .collect::<Vec<_>>()
+                            .join("+")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+    }
+
+    /// whether the item has an icon to resolve
+    pub fn has_icon(&self) -> bool {
+        self.icon_data
+            .as_ref()
+            .is_some_and(|bytes| !bytes.is_empty())
+            || self.icon_name.is_some()
+    }
+
+    /// resolve the item icon, prefering the embedded png over the icon name
+    /// may look up the icon theme, so keep it off the ui thread
+    pub fn icon(&self) -> Option<FdIcon> {
+        self.icon_data
+            .as_ref()
+            .filter(|bytes| !bytes.is_empty())
+            .map(|bytes| FdIcon::Image(image::Handle::from_bytes(bytes.clone())))
+            .or_else(|| self.icon_name.as_deref().and_then(fdo_icons::find))
+    }
+}
+
+/// a single underscore marks the next char as the mnemonic, `__` is a literal underscore
+fn strip_mnemonic(label: &str) -> String {
+    let mut out = String::with_capacity(label.len());
+    let mut chars = label.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '_' {
+            if chars.next_if_eq(&'_').is_some() {
+                out.push('_');
+            }
+            continue;
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
+/// a menu layout along with the proxy of the menu it belongs to
+#[derive(Clone, Debug)]
+pub struct TrayMenu {
+    pub layout: TrayLayout,
+    pub proxy: DBusMenuProxy<'static>,
+}
+
+#[derive(Clone, Copy, Debug, Display)]
+#[display(rename_all = "lowercase")]
+pub enum MenuEvent {
+    Clicked,
+    Hovered,
+    Opened,
+    Closed,
+}
+
+impl DBusMenuProxy<'_> {
+    /// send a menu event, the standard events carry an int32 0 as data
+    pub async fn send_event(&self, id: TrayMenuItemId, event: MenuEvent) -> zbus::Result<()> {
+        let data = zvariant::Value::from(0i32).try_to_owned()?;
+        let timestamp = EVENT_EPOCH.elapsed().as_millis() as u32;
+
+        self.event(*id, &event.to_string(), &data, timestamp).await
+    }
 }
 
 #[proxy(interface = "com.canonical.dbusmenu")]
@@ -381,4 +632,11 @@ pub trait DBusMenu {
 
     #[zbus(signal)]
     fn layout_updated(&self, revision: u32, parent: i32) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn items_properties_updated(
+        &self,
+        updated_props: Vec<(i32, TrayLayoutProps)>,
+        removed_props: Vec<(i32, Vec<String>)>,
+    ) -> zbus::Result<()>;
 }
@@ -2,15 +2,15 @@ use iced::futures::{
     Stream, StreamExt,
     stream::{self, BoxStream, select, select_all},
 };
-use tracing::debug;
+use tracing::{debug, info};
 use zbus::proxy::CacheProperties;
 
 use crate::{
     tray::{
         TrayMenuItemId,
         dbus::{
-            DBusMenuProxy, StatusNotifierItemProxy, StatusNotifierWatcher,
-            StatusNotifierWatcherProxy, TrayLayout, icons_to_fd_icon,
+            DBusMenuProxy, MenuEvent, StatusNotifierItemProxy, StatusNotifierWatcher,
+            StatusNotifierWatcherProxy, TrayLayout, TrayLayoutProps, TrayMenu, icons_to_fd_icon,
         },
     },
     widget::fdo_icons::{self, FdIcon},
@@ -56,15 +56,17 @@ impl SNItem {
         let (_, menu) = menu_proxy.get_layout(0, -1, &[]).await?;
 
         let title = item_proxy.title().await.ok().unwrap_or_default();
-        let tool_tip =
-            item_proxy
-                .tool_tip()
-                .await
-                .ok()
-                .map(|(icon_name, icons, title, description)| {
-                    let icon = icons_to_fd_icon(icons).or_else(|| fdo_icons::find(&icon_name));
-                    (icon, title, description)
-                });
+        let theme_path = item_proxy.icon_theme_path().await.ok();
+        let tool_tip = match item_proxy.tool_tip().await.ok() {
+            Some((icon_name, icons, title, description)) => {
+                let icon = match icons_to_fd_icon(icons) {
+                    Some(icon) => Some(icon),
+                    None => find_icon(icon_name, theme_path).await,
+                };
+                Some((icon, title, description))
+            }
+            None => None,
+        };
 
         Ok(Self {
             title,
@@ -77,21 +79,42 @@ impl SNItem {
         })
     }
 
-    pub async fn menu_item_clicked(&self, id: TrayMenuItemId) -> anyhow::Result<TrayLayout> {
-        let value = zbus::zvariant::Value::I32(32).try_to_owned()?;
+    pub fn tray_menu(&self) -> TrayMenu {
+        TrayMenu {
+            layout: self.menu.clone(),
+            proxy: self.menu_proxy.clone(),
+        }
+    }
 
-        self.menu_proxy
-            .event(
-                *id,
-                "clicked",
-                &value,
-                time::OffsetDateTime::now_utc().microsecond(),
-            )
-            .await?;
+    /// the app answers with LayoutUpdated/ItemsPropertiesUpdated if the click changed the menu
+    pub async fn menu_item_clicked(&self, id: TrayMenuItemId) -> anyhow::Result<()> {
+        self.menu_proxy.send_event(id, MenuEvent::Clicked).await?;
+        Ok(())
+    }
 
-        let (_, layout) = self.menu_proxy.get_layout(0, -1, &[]).await?;
+    /// replace the sub tree of the menu the layout belongs to
+    pub fn update_menu(&mut self, layout: TrayLayout) {
+        match self.menu.find_mut(layout.id) {
+            Some(item) => *item = layout,
+            None => debug!("{} menu item {} not found", self.name, layout.id),
+        }
+    }
 
-        Ok(layout)
+    pub fn update_menu_props(
+        &mut self,
+        updated: Vec<(TrayMenuItemId, TrayLayoutProps)>,
+        removed: Vec<(TrayMenuItemId, Vec<String>)>,
+    ) {
+        for (id, props) in updated {
+            if let Some(item) = self.menu.find_mut(id) {
+                item.props.merge(props);
+            }
+        }
+        for (id, names) in removed {
+            if let Some(item) = self.menu.find_mut(id) {
+                item.props.remove(&names);
+            }
+        }
     }
 
     async fn get_eventstream(&self) -> BoxStream<'static, SNItemEvent> {
@@ -127,6 +150,11 @@ impl SNItem {
                 .await
                 .map(|s| s.map(|_| ()).boxed())
                 .unwrap_or(stream::empty().boxed()),
+            self.item_proxy
+                .receive_new_icon_theme_path()
+                .await
+                .map(|s| s.map(|_| ()).boxed())
+                .unwrap_or(stream::empty().boxed()),
         ])
         .filter_map({
             let proxy = self.item_proxy.clone();
@@ -142,34 +170,74 @@ impl SNItem {
         })
         .boxed();
 
-        let layout_updated_stream =
-            self.menu_proxy
-                .receive_layout_updated()
-                .await
-                .map(|layout_updated| {
-                    layout_updated
-                        .filter_map({
-                            let name = self.name.clone();
-                            let menu_proxy = self.menu_proxy.clone();
-                            move |_| {
-                                debug!("layout update event name {}", &name);
-
-                                let name = name.clone();
-                                let menu_proxy = menu_proxy.clone();
-                                async move {
-                                    menu_proxy.get_layout(0, -1, &[]).await.ok().map(
-                                        |(_, layout)| {
-                                            SNItemEvent::MenuLayoutChanged(name.clone(), layout)
-                                        },
-                                    )
-                                }
+        let layout_updated_stream = self
+            .menu_proxy
+            .receive_layout_updated()
+            .await
+            .map(|layout_updated| {
+                layout_updated
+                    .filter_map({
+                        let name = self.name.clone();
+                        let menu_proxy = self.menu_proxy.clone();
+                        move |layout_updated| {
+                            let name = name.clone();
+                            let menu_proxy = menu_proxy.clone();
+                            async move {
+                                // only refetch the sub tree that changed
+                                let parent = layout_updated.args().ok()?.parent;
+                                debug!("layout update event name {name}, parent {parent}");
+
+                                menu_proxy.get_layout(parent, -1, &[]).await.ok().map(
+                                    |(_, layout)| {
+                                        SNItemEvent::MenuLayoutChanged(name.clone(), layout)
+                                    },
+                                )
                             }
-                        })
-                        .boxed()
-                })
-                .unwrap_or(stream::empty().boxed());
-
-        select_all([icon_change_stream, layout_updated_stream]).boxed()
+                        }
+                    })
+                    .boxed()
+            })
+            .unwrap_or(stream::empty().boxed());
+
+        let props_updated_stream = self
+            .menu_proxy
+            .receive_items_properties_updated()
+            .await
+            .map(|props_updated| {
+                props_updated
+                    .filter_map({
+                        let name = self.name.clone();
+                        move |props_updated| {
+                            let name = name.clone();
+                            async move {
+                                let args = props_updated.args().ok()?;
+                                debug!("props update event name {name}");
+
+                                let updated = args
+                                    .updated_props
+                                    .into_iter()
+                                    .map(|(id, props)| (TrayMenuItemId::from(id), props))
+                                    .collect();
+                                let removed = args
+                                    .removed_props
+                                    .into_iter()
+                                    .map(|(id, names)| (TrayMenuItemId::from(id), names))
+                                    .collect();
+
+                                Some(SNItemEvent::MenuPropsChanged(name, updated, removed))
+                            }
+                        }
+                    })
+                    .boxed()
+            })
+            .unwrap_or(stream::empty().boxed());
+
+        select_all([
+            icon_change_stream,
+            layout_updated_stream,
+            props_updated_stream,
+        ])
+        .boxed()
     }
 
     async fn get_icon(item_proxy: &StatusNotifierItemProxy<'static>) -> Option<FdIcon> {
@@ -179,20 +247,40 @@ impl SNItem {
             .ok()
             .and_then(icons_to_fd_icon);
 
-        item_proxy
-            .icon_name()
-            .await
-            .ok()
-            .as_deref()
-            .and_then(fdo_icons::find)
-            .or(icon_pixmap)
+        let theme_path = item_proxy.icon_theme_path().await.ok();
+
+        let icon = match item_proxy.icon_name().await.ok() {
+            Some(icon_name) => find_icon(icon_name, theme_path).await,
+            None => None,
+        };
+        icon.or(icon_pixmap)
     }
 }
 
+/// look up an icon on a blocking thread, the theme path is walked on disk
+async fn find_icon(icon_name: String, theme_path: Option<String>) -> Option<FdIcon> {
+    tokio::task::spawn_blocking(move || {
+        fdo_icons::find_with_theme_path(&icon_name, theme_path.as_deref())
+    })
+    .await
+    .inspect_err(|err| debug!("icon lookup err: {err}"))
+    .ok()
+    .flatten()
+}
+
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone)]
 pub enum SNItemEvent {
     IconChanged(String, FdIcon),
+    /// sub tree of the menu that changed
     MenuLayoutChanged(String, TrayLayout),
+    MenuPropsChanged(
+        String,
+        /// updated props
+        Vec<(TrayMenuItemId, TrayLayoutProps)>,
+        /// removed props
+        Vec<(TrayMenuItemId, Vec<String>)>,
+    ),
 }
 
 impl SNItemEvent {
@@ -230,9 +318,16 @@ pub enum TrayEvent {
     RegisteredItems(Vec<SNItem>),
 }
 
+/// the events of the registration stream, watcher loss is handled by `listen`
+enum RegistrationEvent {
+    Tray(TrayEvent),
+    /// the watcher we are a host of went away
+    WatcherLost,
+}
+
 async fn get_registration_stream(
     conn: &zbus::Connection,
-) -> anyhow::Result<BoxStream<'static, TrayEvent>> {
+) -> anyhow::Result<BoxStream<'static, RegistrationEvent>> {
     let watcher_proxy = StatusNotifierWatcherProxy::new(conn).await?;
 
     let registered_stream = watcher_proxy
@@ -248,7 +343,10 @@ async fn get_registration_stream(
                         Ok(args) => {
                             let item = SNItem::new(&conn, args.service.to_string()).await;
 
-                            item.map(Box::new).map(TrayEvent::ItemRegistered).ok()
+                            item.map(Box::new)
+                                .map(TrayEvent::ItemRegistered)
+                                .map(RegistrationEvent::Tray)
+                                .ok()
                         }
                         _ => None,
                     }
@@ -264,18 +362,25 @@ async fn get_registration_stream(
             debug!("unregistered {e:?}");
 
             match e.args() {
-                Ok(args) => Some(TrayEvent::ItemUnregistered(args.service.to_string())),
+                Ok(args) => Some(RegistrationEvent::Tray(TrayEvent::ItemUnregistered(
+                    args.service.to_string(),
+                ))),
                 _ => None,
             }
         })
         .boxed();
 
-    Ok(select(registered_stream, unregistered_stream).boxed())
+    let watcher_lost_stream = StatusNotifierWatcher::watcher_lost_stream(conn)
+        .await?
+        .map(|_| RegistrationEvent::WatcherLost)
+        .boxed();
+
+    Ok(select_all([registered_stream, unregistered_stream, watcher_lost_stream]).boxed())
 }
 
 enum StreamState {
     Init,
-    Active(zbus::Connection, BoxStream<'static, TrayEvent>),
+    Active(zbus::Connection, BoxStream<'static, RegistrationEvent>),
     Error,
 }
 
@@ -300,7 +405,11 @@ pub fn listen() -> impl Stream<Item = anyhow::Result<TrayEvent>> {
                 };
 
                 let item_es = SNItemEvent::eventstream(items.clone()).await;
-                let es = select(reg_es, item_es.map(Box::new).map(TrayEvent::SNItem)).boxed();
+                let item_es = item_es
+                    .map(Box::new)
+                    .map(TrayEvent::SNItem)
+                    .map(RegistrationEvent::Tray);
+                let es = select(reg_es, item_es).boxed();
 
                 Some((
                     Some(Ok(TrayEvent::RegisteredItems(items))),
@@ -308,13 +417,22 @@ pub fn listen() -> impl Stream<Item = anyhow::Result<TrayEvent>> {
                 ))
             }
             StreamState::Active(conn, mut es) => match es.next().await {
-                Some(event) => {
+                Some(RegistrationEvent::WatcherLost) => {
+                    info!("Tray watcher lost, restarting");
+                    drop(conn);
+                    Some((
+                        Some(Ok(TrayEvent::RegisteredItems(Vec::new()))),
+                        StreamState::Init,
+                    ))
+                }
+                Some(RegistrationEvent::Tray(event)) => {
                     if let TrayEvent::ItemRegistered(item) = event.clone() {
                         let item_es = item
                             .get_eventstream()
                             .await
                             .map(Box::new)
                             .map(TrayEvent::SNItem)
+                            .map(RegistrationEvent::Tray)
                             .boxed();
                         es = select(es, item_es).boxed();
                     };
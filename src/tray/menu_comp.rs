@@ -1,21 +1,26 @@
+use std::collections::HashMap;
+
 use iced::{
-    Color, Element, Length, Task,
+    Color, Element, Length, Subscription, Task, Theme,
     advanced::graphics::futures::MaybeSend,
     alignment::Vertical,
-    border, padding,
-    widget::{Column, Row, button, container, row, text, toggler},
+    border,
+    keyboard::{self, Key, key::Named},
+    padding,
+    widget::{Column, Row, Space, button, container, mouse_area, row, rule, text, toggler},
 };
 use iced_layershell::actions::{IcedNewMenuSettings, MenuDirection};
 use lucide_icons::Icon;
-use tracing::info;
+use tracing::{debug, info};
 
 use crate::{
     feature::{Comp, Feature},
     theme::CAT_THEME,
     tray::{
-        TrayLayoutProps,
-        dbus::{TrayLayout, TrayMenuItemId},
+        TrayLayoutProps, TrayMenu,
+        dbus::{DBusMenuProxy, MenuEvent, TrayLayout, TrayMenuItemId},
     },
+    widget::fdo_icons::FdIcon,
 };
 
 #[derive(Debug, Clone)]
@@ -23,20 +28,177 @@ pub enum Message {
     OpenSubMenu(TrayMenuItemId),
     CloseSubMenu,
     ItemSelected(String, TrayMenuItemId),
+    SubMenuUpdated(TrayLayout),
+    /// the app changed the layout of a menu, forwarded by the daemon
+    LayoutChanged(String, TrayLayout),
+    /// the app changed item props of a menu, forwarded by the daemon
+    PropsChanged(
+        String,
+        Vec<(TrayMenuItemId, TrayLayoutProps)>,
+        Vec<(TrayMenuItemId, Vec<String>)>,
+    ),
+    IconsLoaded(HashMap<TrayMenuItemId, Option<FdIcon>>),
+    Hovered(TrayMenuItemId),
+    Close,
+
+    // keyboard navigation
+    FocusNext,
+    FocusPrev,
+    Activate,
+    Expand,
+    Back,
 }
 
 pub struct Init {
     pub name: String,
-    pub layout: TrayLayout,
+    pub menu: TrayMenu,
 }
 
 pub struct MenuComp {
     name: String,
     layout: TrayLayout,
+    proxy: DBusMenuProxy<'static>,
+    /// None when the icon wasn't found
+    icons: HashMap<TrayMenuItemId, Option<FdIcon>>,
     menu_stack: Vec<TrayMenuItemId>,
+    focused: Option<TrayMenuItemId>,
+}
+
+fn item_style(_: &Theme, status: button::Status) -> button::Style {
+    let theme = &CAT_THEME;
+    let base = button::Style {
+        background: Some(Color::TRANSPARENT.into()),
+        text_color: theme.text_color(),
+        ..Default::default()
+    };
+    match status {
+        button::Status::Hovered => button::Style {
+            background: Some(theme.surface0().into()),
+            ..base
+        },
+        button::Status::Disabled => button::Style {
+            text_color: theme.overlay0(),
+            ..base
+        },
+        _ => base,
+    }
+}
+
+/// collect the icons of the layout tree, so they are not resolved on every view
+fn collect_icons(layout: &TrayLayout, icons: &mut HashMap<TrayMenuItemId, Option<FdIcon>>) {
+    for child in layout.children.iter() {
+        if child.props.has_icon() {
+            icons.insert(child.id, child.props.icon());
+        }
+        collect_icons(child, icons);
+    }
+}
+
+/// resolve the icons of the layout tree on a blocking thread
+fn load_icons(layout: TrayLayout) -> Task<Message> {
+    Task::future(async move {
+        tokio::task::spawn_blocking(move || {
+            let mut icons = HashMap::new();
+            collect_icons(&layout, &mut icons);
+            icons
+        })
+        .await
+        .inspect_err(|err| {
+            info!("Error loading menu icons: {err:?}");
+        })
+        .unwrap_or_default()
+    })
+    .map(Message::IconsLoaded)
+}
+
+/// notify the app that a (sub)menu is about to be shown,
+/// and refetch that part of the layout if the app reports it changed
+async fn about_to_show(proxy: DBusMenuProxy<'static>, id: TrayMenuItemId) -> Option<TrayLayout> {
+    let needs_update = proxy
+        .about_to_show(*id)
+        .await
+        .inspect_err(|err| debug!("about to show err: {err}"))
+        .ok()?;
+
+    if !needs_update {
+        return None;
+    }
+
+    proxy
+        .get_layout(*id, -1, &[])
+        .await
+        .inspect_err(|err| debug!("get layout err: {err}"))
+        .ok()
+        .map(|(_, layout)| layout)
 }
 
 impl MenuComp {
+    /// items in the current menu that can be focused with the keyboard
+    fn focusable(&self) -> Vec<&TrayLayout> {
+        Self::get_current_menu(&self.menu_stack, &self.layout)
+            .iter()
+            .filter(|item| {
+                item.props.is_visible()
+                    && item.props.is_enabled()
+                    && !item.props.is_separator()
+                    && item.props.label.is_some()
+            })
+            .collect()
+    }
+
+    fn focused_item(&self) -> Option<&TrayLayout> {
+        self.focused
+            .and_then(|id| self.focusable().into_iter().find(|item| item.id == id))
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        let items = self.focusable();
+        let pos = self
+            .focused
+            .and_then(|id| items.iter().position(|item| item.id == id));
+
+        let next = match (pos, forward) {
+            (None, true) => items.first(),
+            (None, false) => items.last(),
+            (Some(pos), true) => items.get(pos + 1).or(items.last()),
+            (Some(pos), false) => items.get(pos.saturating_sub(1)),
+        };
+
+        self.focused = next.map(|item| item.id);
+    }
+
+    fn send_event(&self, id: TrayMenuItemId, event: MenuEvent) -> Task<Message> {
+        let proxy = self.proxy.clone();
+        Task::future(async move {
+            if let Err(err) = proxy.send_event(id, event).await {
+                debug!("menu event {event} err: {err}");
+            }
+        })
+        .discard()
+    }
+
+    fn open_sub_menu(&mut self, id: TrayMenuItemId) -> Task<Message> {
+        self.menu_stack.push(id);
+        self.focused = None;
+
+        let update = Task::future(about_to_show(self.proxy.clone(), id))
+            .and_then(|layout| Task::done(Message::SubMenuUpdated(layout)));
+
+        Task::batch([update, self.send_event(id, MenuEvent::Opened)])
+    }
+
+    /// replace the changed part of the layout
+    fn update_layout(&mut self, layout: TrayLayout) -> Task<Message> {
+        let icons = load_icons(layout.clone());
+        if let Some(item) = self.layout.find_mut(layout.id) {
+            *item = layout;
+        }
+        if self.focused_item().is_none() {
+            self.focused = None;
+        }
+        icons
+    }
+
     fn get_current_menu<'a>(
         stack: &'a [TrayMenuItemId],
         layout: &'a TrayLayout,
@@ -50,96 +212,107 @@ impl MenuComp {
         }
     }
 
+    /// the leading slot of an item: toggle indicator, item icon or submenu marker
+    fn view_leading<'a>(&'a self, layout: &'a TrayLayout) -> Element<'a, Message> {
+        let theme = &CAT_THEME;
+        let size = theme.spacing().md();
+        let is_on = layout.props.toggle_state.is_some_and(|state| state == 1);
+
+        let icon = match (&layout.props.toggle_type, self.icons.get(&layout.id)) {
+            (Some(toggle_type), _) if toggle_type == "radio" => Some(
+                if is_on { Icon::CircleDot } else { Icon::Circle }
+                    .widget()
+                    .size(size)
+                    .into(),
+            ),
+            (_, Some(Some(icon))) => Some(icon.elem(size)),
+            // still loading, keep the slot empty
+            (_, None) if layout.props.has_icon() => None,
+            (_, _) if layout.props.is_submenu() => {
+                Some(Icon::DiamondPlus.widget().size(size).into())
+            }
+            _ => None,
+        };
+
+        container(icon.unwrap_or(Space::new().into()))
+            .center_y(Length::Fill)
+            .width(theme.spacing().lg())
+            .into()
+    }
+
     fn view_menu<'a>(&'a self, name: &'a str, layout: &'a TrayLayout) -> Element<'a, Message> {
         let theme = &CAT_THEME;
-        match &layout.props {
+        let props = &layout.props;
+        let enabled = props.is_enabled();
+
+        match props {
             // Divider
-            TrayLayoutProps { type_: Some(t), .. } if t == "separator" => {
-                // info!("sep");
-                container(text!("---")).into()
-            }
+            _ if props.is_separator() => container(rule::horizontal(1).style(|_| rule::Style {
+                color: theme.surface1(),
+                radius: 0.0.into(),
+                fill_mode: rule::FillMode::Padded(theme.spacing().xs() as u16),
+                snap: true,
+            }))
+            .center_y(Length::Fill)
+            .into(),
             // toggle state
             TrayLayoutProps {
-                label: Some(label),
+                label: Some(_),
                 toggle_type: Some(togg_type),
                 toggle_state: Some(togg_state),
                 ..
-            } if togg_type == "checkmark" => toggler(*togg_state > 0)
-                .label(label.replace("_", ""))
+            } if togg_type == "checkmark" => toggler(*togg_state == 1)
+                .label(props.label_text().unwrap_or_default())
                 .text_size(theme.spacing().md())
                 .width(Length::Fill)
                 .size(theme.spacing().sm())
-                .on_toggle({
+                .on_toggle_maybe(enabled.then(|| {
                     let id = layout.id;
                     move |_| Message::ItemSelected(name.to_string(), id)
-                })
+                }))
                 .into(),
-            // sub menu
-            TrayLayoutProps {
-                children_display: Some(display),
-                label: Some(label),
-                ..
-            } if display == "submenu" => {
-                let icon = Icon::DiamondPlus
-                    .widget()
-                    .size(theme.spacing().md())
-                    .align_y(Vertical::Center)
-                    .width(theme.spacing().lg());
-
-                let label = text(label.clone().replace("_", ""))
+            // sub menu, radio and regular buttons
+            TrayLayoutProps { label: Some(_), .. } => {
+                let label = text(props.label_text().unwrap_or_default())
                     .align_y(Vertical::Center)
+                    .width(Length::Fill)
                     .size(theme.spacing().md());
 
-                let button_content = row![icon, label]
+                let trailing: Element<'a, Message> = if props.is_submenu() {
+                    Icon::ChevronRight
+                        .widget()
+                        .size(theme.spacing().md())
+                        .align_y(Vertical::Center)
+                        .into()
+                } else if let Some(shortcut) = props.shortcut_text() {
+                    text(shortcut)
+                        .align_y(Vertical::Center)
+                        .size(theme.spacing().sm() + theme.spacing().xxs())
+                        .color(theme.subtext0())
+                        .into()
+                } else {
+                    Space::new().into()
+                };
+
+                let button_content = row![self.view_leading(layout), label, trailing]
                     .spacing(theme.spacing().xs())
                     .align_y(Vertical::Center);
 
-                button(button_content)
-                    .style(|_, status| {
-                        let base = button::Style {
-                            background: Some(Color::TRANSPARENT.into()),
-                            text_color: theme.text_color(),
-                            ..Default::default()
-                        };
-                        match status {
-                            button::Status::Hovered => button::Style {
-                                background: Some(theme.surface0().into()),
-                                ..base
-                            },
-                            _ => base,
-                        }
-                    })
-                    .padding(padding::vertical(theme.spacing().xxs()).left(theme.spacing().xxs()))
-                    .width(Length::Fill)
-                    .on_press(Message::OpenSubMenu(layout.id))
-                    .into()
-            }
-            // regular button
-            TrayLayoutProps {
-                label: Some(label), ..
-            } => {
-                let label = text(label.clone().replace("_", ""))
-                    .align_y(Vertical::Center)
-                    .size(theme.spacing().md());
+                let on_press = if props.is_submenu() {
+                    Message::OpenSubMenu(layout.id)
+                } else {
+                    Message::ItemSelected(name.to_string(), layout.id)
+                };
 
-                button(label)
-                    .style(|_, status| {
-                        let base = button::Style {
-                            background: Some(Color::TRANSPARENT.into()),
-                            text_color: theme.text_color(),
-                            ..Default::default()
-                        };
-                        match status {
-                            button::Status::Hovered => button::Style {
-                                background: Some(theme.surface0().into()),
-                                ..base
-                            },
-                            _ => base,
-                        }
-                    })
-                    .padding(padding::left(theme.spacing().xl()).vertical(theme.spacing().xxs()))
+                button(button_content)
+                    .style(item_style)
+                    .padding(
+                        padding::vertical(theme.spacing().xxs())
+                            .left(theme.spacing().xxs())
+                            .right(theme.spacing().sm()),
+                    )
                     .width(Length::Fill)
-                    .on_press(Message::ItemSelected(name.to_string(), layout.id))
+                    .on_press_maybe(enabled.then_some(on_press))
                     .into()
             }
             _ => {
@@ -156,29 +329,117 @@ impl Comp for MenuComp {
 
     fn new<O: MaybeSend + 'static>(
         input: Self::Init,
-        _f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
+        f: impl Fn(Self::Message) -> O + MaybeSend + 'static,
     ) -> (Self, Task<O>) {
-        let layout = &input.layout;
+        let TrayMenu { layout, proxy } = input.menu;
         info!("layout: {layout:?}");
-        Self {
+        let icons = load_icons(layout.clone());
+
+        let root_id = layout.id;
+        let comp = Self {
             name: input.name,
-            layout: input.layout,
+            layout,
+            proxy,
+            icons: HashMap::new(),
             menu_stack: Vec::new(),
-        }
-        .to_tuple()
+            focused: None,
+        };
+
+        let update = Task::future(about_to_show(comp.proxy.clone(), root_id))
+            .and_then(|layout| Task::done(Message::SubMenuUpdated(layout)));
+        let opened = comp.send_event(root_id, MenuEvent::Opened);
+
+        (comp, Task::batch([icons, update, opened]).map(f))
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        keyboard::listen()
+            .filter_map(|event| match event {
+                keyboard::Event::KeyPressed { key, .. } => Some(key),
+                _ => None,
+            })
+            .filter_map(|key| match key.as_ref() {
+                Key::Character("j") | Key::Named(Named::ArrowDown) => Some(Message::FocusNext),
+                Key::Character("k") | Key::Named(Named::ArrowUp) => Some(Message::FocusPrev),
+                Key::Character("l") | Key::Named(Named::ArrowRight) => Some(Message::Expand),
+                Key::Character("h") | Key::Named(Named::ArrowLeft) => Some(Message::CloseSubMenu),
+                Key::Named(Named::Enter) => Some(Message::Activate),
+                Key::Named(Named::Escape) => Some(Message::Back),
+                _ => None,
+            })
     }
 
     fn update(&mut self, message: Self::Message) -> iced::Task<Self::Message> {
         match message {
-            Message::OpenSubMenu(id) => {
-                self.menu_stack.push(id);
+            Message::OpenSubMenu(id) => self.open_sub_menu(id),
+            Message::CloseSubMenu => {
+                let parent = self.menu_stack.pop();
+                // keep the keyboard focus on the item the sub menu was opened from
+                if self.focused.is_some() {
+                    self.focused = parent;
+                }
+                parent
+                    .map(|id| self.send_event(id, MenuEvent::Closed))
+                    .unwrap_or_default()
+            }
+            Message::Hovered(id) => self.send_event(id, MenuEvent::Hovered),
+            Message::SubMenuUpdated(layout) => self.update_layout(layout),
+            Message::LayoutChanged(name, layout) if name == self.name => self.update_layout(layout),
+            Message::PropsChanged(name, updated, removed) if name == self.name => {
+                for (id, props) in updated {
+                    if let Some(item) = self.layout.find_mut(id) {
+                        item.props.merge(props);
+                        self.icons.remove(&id);
+                    }
+                }
+                for (id, names) in removed {
+                    if let Some(item) = self.layout.find_mut(id) {
+                        item.props.remove(&names);
+                        self.icons.remove(&id);
+                    }
+                }
+                if self.focused_item().is_none() {
+                    self.focused = None;
+                }
+                load_icons(self.layout.clone())
+            }
+            Message::LayoutChanged(..) | Message::PropsChanged(..) => Task::none(),
+            Message::IconsLoaded(icons) => {
+                self.icons.extend(icons);
                 Task::none()
             }
-            Message::CloseSubMenu => {
-                self.menu_stack.pop();
+            Message::FocusNext => {
+                self.move_focus(true);
+                Task::none()
+            }
+            Message::FocusPrev => {
+                self.move_focus(false);
                 Task::none()
             }
-            Message::ItemSelected(_name, _id) => Task::none(),
+            Message::Activate => match self.focused_item() {
+                Some(item) if item.props.is_submenu() => {
+                    let id = item.id;
+                    self.open_sub_menu(id).chain(Task::done(Message::FocusNext))
+                }
+                Some(item) => Task::done(Message::ItemSelected(self.name.clone(), item.id)),
+                None => Task::none(),
+            },
+            Message::Expand => match self.focused_item() {
+                Some(item) if item.props.is_submenu() => {
+                    let id = item.id;
+                    self.open_sub_menu(id).chain(Task::done(Message::FocusNext))
+                }
+                _ => Task::none(),
+            },
+            Message::Back => {
+                if self.menu_stack.is_empty() {
+                    Task::done(Message::Close)
+                } else {
+                    Task::done(Message::CloseSubMenu)
+                }
+            }
+            Message::Close => self.send_event(self.layout.id, MenuEvent::Closed),
+            Message::ItemSelected(_, _) => Task::none(),
         }
     }
 
@@ -188,43 +449,49 @@ impl Comp for MenuComp {
 
         let mut top_menu = menu_items
             .iter()
+            .filter(|menu| menu.props.is_visible())
             .map(|menu| {
-                Element::from(
-                    container(self.view_menu(&self.name, menu))
-                        .padding(padding::left(theme.spacing().xs()))
-                        .center_y(theme.spacing().lg())
-                        .center_x(Length::Fill),
-                )
+                let is_focused = self.focused == Some(menu.id);
+                let item = container(self.view_menu(&self.name, menu))
+                    .padding(padding::left(theme.spacing().xs()))
+                    .center_y(theme.spacing().lg())
+                    .center_x(Length::Fill)
+                    .style(move |_| container::Style {
+                        background: is_focused.then(|| theme.surface0().into()),
+                        border: border::rounded(theme.radius().sm()),
+                        ..Default::default()
+                    });
+
+                Element::from(mouse_area(item).on_enter(Message::Hovered(menu.id)))
             })
             .fold(Column::new(), |col, item_elem| col.push(item_elem));
 
         if !self.menu_stack.is_empty() {
             top_menu = top_menu.push({
+                let icon = container(Icon::ChevronLeft.widget().size(theme.spacing().md()))
+                    .center_y(Length::Fill)
+                    .width(theme.spacing().lg());
+
                 let label = text("Back")
                     .align_y(Vertical::Center)
                     .size(theme.spacing().md());
 
                 Element::from(
-                    button(label)
-                        .style(|_, status| {
-                            let base = button::Style {
-                                background: Some(Color::TRANSPARENT.into()),
-                                text_color: theme.text_color(),
-                                ..Default::default()
-                            };
-                            match status {
-                                button::Status::Hovered => button::Style {
-                                    background: Some(theme.surface0().into()),
-                                    ..base
-                                },
-                                _ => base,
-                            }
-                        })
+                    container(
+                        button(
+                            row![icon, label]
+                                .spacing(theme.spacing().xs())
+                                .align_y(Vertical::Center),
+                        )
+                        .style(item_style)
                         .padding(
-                            padding::left(theme.spacing().xl()).vertical(theme.spacing().xxs()),
+                            padding::vertical(theme.spacing().xxs()).left(theme.spacing().xxs()),
                         )
                         .width(Length::Fill)
                         .on_press(Message::CloseSubMenu),
+                    )
+                    .padding(padding::left(theme.spacing().xs()))
+                    .center_y(theme.spacing().lg()),
                 )
             })
         }
@@ -251,7 +518,13 @@ impl Feature for MenuComp {
     fn layer(&self) -> IcedNewMenuSettings {
         let theme = &CAT_THEME;
         let item_height = theme.spacing().lg();
-        let height = self.layout.children.len() as f32 * item_height + theme.spacing().xs();
+        let num_of_items = self
+            .layout
+            .children
+            .iter()
+            .filter(|child| child.props.is_visible())
+            .count();
+        let height = num_of_items as f32 * item_height + theme.spacing().xs();
 
         IcedNewMenuSettings {
             size: (220, height as u32),
@@ -3,7 +3,7 @@ mod eventstream;
 pub mod menu_comp;
 pub mod service;
 pub mod tray_comp;
-pub use dbus::{TrayLayout, TrayLayoutProps, TrayMenuItemId};
+pub use dbus::{TrayLayoutProps, TrayMenu, TrayMenuItemId};
 
 use iced::{Task, advanced::graphics::futures::MaybeSend};
 
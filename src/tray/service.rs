@@ -1,7 +1,7 @@
 use crate::{
     feature::Service,
     tray::{
-        TrayMenuItemId,
+        TrayLayoutProps, TrayMenuItemId,
         dbus::TrayLayout,
         eventstream::{SNItem, SNItemEvent, TrayEvent, listen},
     },
@@ -19,6 +19,11 @@ pub enum Message {
     Registered(Box<SNItem>),
     IconChanged(String, FdIcon),
     MenuLayoutChanged(String, TrayLayout),
+    MenuPropsChanged(
+        String,
+        Vec<(TrayMenuItemId, TrayLayoutProps)>,
+        Vec<(TrayMenuItemId, Vec<String>)>,
+    ),
     Unregistered(String),
     UpdateItems(TrayItems),
 }
@@ -37,13 +42,13 @@ impl TrayService {
         self.items
             .get(&name)
             .map(|item| {
-                let name = item.name.clone();
                 let item = item.clone();
-                Task::future(async move { item.menu_item_clicked(id).await })
-                    .map(|res| res.ok())
-                    .and_then(move |layout| {
-                        Task::done(Message::MenuLayoutChanged(name.clone(), layout))
-                    })
+                Task::future(async move {
+                    if let Err(err) = item.menu_item_clicked(id).await {
+                        error!("Error sending click to {}: {err:}", item.name);
+                    }
+                })
+                .discard()
             })
             .unwrap_or_default()
     }
@@ -82,7 +87,14 @@ impl Service for TrayService {
             Message::MenuLayoutChanged(name, layout) => {
                 debug!("{name} menu layout updated, {layout:?}");
                 if let Some(item) = self.items.get_mut(&name) {
-                    item.menu = layout;
+                    item.update_menu(layout);
+                }
+                Task::none()
+            }
+            Message::MenuPropsChanged(name, updated, removed) => {
+                debug!("{name} menu props updated, {updated:?}, removed {removed:?}");
+                if let Some(item) = self.items.get_mut(&name) {
+                    item.update_menu_props(updated, removed);
                 }
                 Task::none()
             }
@@ -130,6 +142,9 @@ impl From<SNItemEvent> for Message {
         match sni_event {
             SNItemEvent::IconChanged(id, handle) => Message::IconChanged(id, handle),
             SNItemEvent::MenuLayoutChanged(id, layout) => Message::MenuLayoutChanged(id, layout),
+            SNItemEvent::MenuPropsChanged(id, updated, removed) => {
+                Message::MenuPropsChanged(id, updated, removed)
+            }
         }
     }
 }
@@ -10,7 +10,7 @@ use tracing::debug;
 use crate::{
     feature::CompWithProps,
     theme::CAT_THEME,
-    tray::{dbus::TrayLayout, service::TrayService},
+    tray::{TrayMenu, service::TrayService},
     widget::{
         align_center,
         container_ext::ContainExt,
@@ -24,7 +24,7 @@ pub enum Message {
         /// name
         String,
         /// menu layout
-        TrayLayout,
+        TrayMenu,
     ),
 }
 
@@ -78,7 +78,7 @@ impl CompWithProps for TrayComp {
                         ..Default::default()
                     },
                 })
-                .on_press(Message::SnItemClicked(item.name.clone(), item.menu.clone()))
+                .on_press(Message::SnItemClicked(item.name.clone(), item.tray_menu()))
             );
 
             if let Some((icon, title, description)) = item.tool_tip.as_ref() {
@@ -1,5 +1,10 @@
 // original source: https://github.com/MalpenZibo/ashell/blob/main/src/services/tray/mod.rs
-use std::{collections::BTreeSet, env, path::PathBuf, sync::LazyLock};
+use std::{
+    collections::BTreeSet,
+    env,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
 
 use freedesktop_icons::lookup;
 use iced::{
@@ -36,13 +41,66 @@ pub fn find(icon_name: &str) -> Option<FdIcon> {
             .map(image::Handle::from_bytes)
             .map(FdIcon::Image);
     }
+    find_path(icon_name).map(from_path)
+}
+
+/// find the path of an icon, for callers that keep the lookup around
+pub fn find_path(icon_name: &str) -> Option<PathBuf> {
+    if icon_name.is_empty() {
+        return None;
+    }
+    if icon_name.starts_with("/") {
+        let path = PathBuf::from(icon_name);
+        return path.exists().then_some(path);
+    }
     find_icon_path(icon_name)
         .or_else(|| find_similar_icon_path(icon_name))
         .or_else(|| find_prefix_icon_path(icon_name))
-        .map(fd_icon_from_path)
 }
 
-fn fd_icon_from_path(path: PathBuf) -> FdIcon {
+/// find an icon, searching the app provided theme path before the system themes
+/// walks the theme path, so call it off the async runtime
+pub fn find_with_theme_path(icon_name: &str, theme_path: Option<&str>) -> Option<FdIcon> {
+    theme_path
+        .filter(|theme_path| !theme_path.is_empty())
+        .and_then(|theme_path| find_in_dir(icon_name, Path::new(theme_path)))
+        .or_else(|| find(icon_name))
+}
+
+/// look up an icon in a single directory, either flat or laid out like an icon theme
+pub fn find_in_dir(icon_name: &str, dir: &Path) -> Option<FdIcon> {
+    if icon_name.is_empty() || !dir.is_dir() {
+        return None;
+    }
+
+    walkdir::WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().file_stem().is_some_and(|stem| stem == icon_name))
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| ext == "svg" || ext == "png")
+        })
+        .max_by_key(|e| icon_size_rank(e.path()))
+        .map(|e| from_path(e.into_path()))
+}
+
+/// rank svgs first, then rasters by the size in their theme dir, e.g. 48x48/apps/foo.png
+fn icon_size_rank(path: &Path) -> u32 {
+    if path.extension().is_some_and(|ext| ext == "svg") {
+        return u32::MAX;
+    }
+
+    path.ancestors()
+        .filter_map(|dir| dir.file_name()?.to_str())
+        .find_map(|name| name.split_once('x')?.0.parse().ok())
+        .unwrap_or_default()
+}
+
+pub fn from_path(path: PathBuf) -> FdIcon {
     if path.extension().is_some_and(|ext| ext == "svg") {
         debug!("svg icon found. Path: {path:?}");
 